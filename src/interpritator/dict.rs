@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use crate::interpritator::objects::Object;
+
+// ключом словаря бывают только эти типы (Parser::is_key_type)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Int(i32),
+    Long(i128),
+    Short(i8),
+    Char(char),
+    String(String),
+    Bool(bool),
+}
+
+fn key(value: &Object) -> Option<Key> {
+    match value {
+        Object::Int(v) => Some(Key::Int(*v)),
+        Object::Long(v) => Some(Key::Long(*v)),
+        Object::Short(v) => Some(Key::Short(*v)),
+        Object::Char(v) => Some(Key::Char(*v)),
+        Object::String(v) => Some(Key::String(v.clone())),
+        Object::Bool(v) => Some(Key::Bool(*v)),
+        _ => None,
+    }
+}
+
+// пары хранятся в порядке вставки, хеш-таблица даёт позицию пары по ключу
+#[derive(Debug, Clone, Default)]
+pub struct Dict {
+    entries: Vec<(Object, Object)>,
+    index: HashMap<Key, usize>,
+}
+
+impl Dict {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ключ другого типа в индекс не попадает, его ищем перебором
+    fn position(&self, k: &Object) -> Option<usize> {
+        match key(k) {
+            Some(hashed) => self.index.get(&hashed).copied(),
+            None => self.entries.iter().position(|(other, _)| other == k),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, k: &Object) -> Option<&Object> {
+        self.position(k).map(|i| &self.entries[i].1)
+    }
+
+    pub fn contains(&self, k: &Object) -> bool {
+        self.position(k).is_some()
+    }
+
+    // возвращает старое значение, если ключ уже был
+    pub fn insert(&mut self, k: Object, v: Object) -> Option<Object> {
+        if let Some(i) = self.position(&k) {
+            return Some(std::mem::replace(&mut self.entries[i].1, v));
+        }
+        if let Some(hashed) = key(&k) {
+            self.index.insert(hashed, self.entries.len());
+        }
+        self.entries.push((k, v));
+        None
+    }
+
+    // порядок остальных пар сохраняется, поэтому позиции после удалённой сдвигаются
+    pub fn remove(&mut self, k: &Object) -> Option<Object> {
+        let i = self.position(k)?;
+        let (old_key, value) = self.entries.remove(i);
+        if let Some(hashed) = key(&old_key) {
+            self.index.remove(&hashed);
+        }
+        for pos in self.index.values_mut() {
+            if *pos > i {
+                *pos -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Object, Object)> {
+        self.entries.iter()
+    }
+
+    pub fn keys(&self) -> Vec<Object> {
+        self.entries.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    pub fn values(&self) -> Vec<Object> {
+        self.entries.iter().map(|(_, v)| v.clone()).collect()
+    }
+
+    pub fn first(&self) -> Option<&(Object, Object)> {
+        self.entries.first()
+    }
+}
+
+// словари равны, если у них одни и те же пары в любом порядке
+impl PartialEq for Dict {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
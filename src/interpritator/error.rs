@@ -0,0 +1,121 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::interpritator::objects::Object;
+use crate::lexer::token::Position;
+use crate::parser::parser::{Field, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    DivisionByZero,
+    Overflow,
+    Domain,
+    Parse,
+    IndexOutOfBounds,
+    KeyNotFound,
+    EmptyCollection,
+    TypeMismatch,
+    WrongArguments,
+    NoMatch,
+    UndefinedName,
+    UnsupportedOperation,
+    Io,
+    NotFound,
+    PermissionDenied,
+    LimitExceeded,
+    Exit(i32),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuntimeError {
+    kind: ErrorKind,
+    message: String,
+    pos: Option<Position>,
+}
+
+impl RuntimeError {
+    pub fn new(kind: ErrorKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            pos: None,
+        }
+    }
+
+    // место ставится один раз, самым глубоким узлом
+    pub fn at(mut self, pos: Position) -> Self {
+        if self.pos.is_none() {
+            self.pos = Some(pos);
+        }
+        self
+    }
+
+    // quit(code) раскручивает стек как ошибка, которую нельзя поймать
+    pub fn exit(code: i32) -> Self {
+        Self::new(ErrorKind::Exit(code), format!("quit({})", code))
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        match self.kind {
+            ErrorKind::Exit(code) => Some(code),
+            _ => None,
+        }
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        matches!(self.kind, ErrorKind::LimitExceeded | ErrorKind::Exit(_))
+    }
+
+    // значение, которое получает catch
+    pub fn to_object(&self) -> Object {
+        let (line, column) = match self.pos {
+            Some(pos) => (pos.line as i32, pos.column as i32),
+            None => (0, 0),
+        };
+        let fields = vec![
+            ("kind".to_string(), Object::String(self.kind.to_string())),
+            ("message".to_string(), Object::String(self.message.clone())),
+            ("line".to_string(), Object::Int(line)),
+            ("column".to_string(), Object::Int(column)),
+        ];
+        Object::Struct("Error".to_string(), Rc::new(RefCell::new(fields)))
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pos {
+            Some(pos) => write!(f, "error[{}] at {}: {}", self.kind, pos, self.message),
+            None => write!(f, "error[{}]: {}", self.kind, self.message),
+        }
+    }
+}
+
+// встроенная структура Error, её видят и парсер и интерпретатор
+pub fn error_struct_fields() -> Vec<Field> {
+    vec![
+        Field {
+            name: "kind".to_string(),
+            field_type: Type::String,
+        },
+        Field {
+            name: "message".to_string(),
+            field_type: Type::String,
+        },
+        Field {
+            name: "line".to_string(),
+            field_type: Type::Int,
+        },
+        Field {
+            name: "column".to_string(),
+            field_type: Type::Int,
+        },
+    ]
+}
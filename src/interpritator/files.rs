@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crate::Object;
+use crate::interpritator::error::{ErrorKind, RuntimeError};
+use crate::interpritator::systemFunctions::wrong_args;
+
+pub const FUNCTIONS: [&str; 8] = [
+    "read_file",
+    "write_file",
+    "append_file",
+    "read_lines",
+    "file_exists",
+    "remove_file",
+    "list_dir",
+    "make_dir",
+];
+
+pub fn is_file_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+fn io_error(name: &str, path: &str, e: io::Error) -> RuntimeError {
+    let kind = match e.kind() {
+        io::ErrorKind::NotFound => ErrorKind::NotFound,
+        _ => ErrorKind::Io,
+    };
+    RuntimeError::new(kind, format!("{}: '{}': {}", name, path, e))
+}
+
+fn strings(items: Vec<String>) -> Object {
+    Object::List(Rc::new(RefCell::new(
+        items.into_iter().map(Object::String).collect(),
+    )))
+}
+
+pub fn call(name: &str, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match (name, args.as_slice()) {
+        ("read_file", [Object::String(path)]) => fs::read_to_string(path)
+            .map(Object::String)
+            .map_err(|e| io_error(name, path, e)),
+        ("read_lines", [Object::String(path)]) => fs::read_to_string(path)
+            .map(|text| strings(text.lines().map(String::from).collect()))
+            .map_err(|e| io_error(name, path, e)),
+        ("write_file", [Object::String(path), Object::String(text)]) => fs::write(path, text)
+            .map(|_| Object::Void)
+            .map_err(|e| io_error(name, path, e)),
+        ("append_file", [Object::String(path), Object::String(text)]) => fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map(|_| Object::Void)
+            .map_err(|e| io_error(name, path, e)),
+        ("file_exists", [Object::String(path)]) => {
+            Ok(Object::Bool(std::path::Path::new(path).exists()))
+        }
+        ("remove_file", [Object::String(path)]) => fs::remove_file(path)
+            .map(|_| Object::Void)
+            .map_err(|e| io_error(name, path, e)),
+        // имена без пути, отсортированы, чтобы вывод не зависел от файловой системы
+        ("list_dir", [Object::String(path)]) => {
+            let mut names = Vec::new();
+            for entry in fs::read_dir(path).map_err(|e| io_error(name, path, e))? {
+                let entry = entry.map_err(|e| io_error(name, path, e))?;
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+            names.sort();
+            Ok(strings(names))
+        }
+        ("make_dir", [Object::String(path)]) => fs::create_dir_all(path)
+            .map(|_| Object::Void)
+            .map_err(|e| io_error(name, path, e)),
+        _ => Err(wrong_args(name, expected(name), &args)),
+    }
+}
+
+fn expected(name: &str) -> &'static str {
+    match name {
+        "write_file" | "append_file" => "(string, string)",
+        _ => "one string",
+    }
+}
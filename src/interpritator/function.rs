@@ -1,4 +1,10 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use crate::expressionNode;
+use crate::interpritator::interpritator::Definitions;
+use crate::interpritator::objects::Object;
 use crate::parser::parser::{Arg, Type};
 use crate::scopeNode;
 
@@ -40,3 +46,30 @@ impl function {
         self.return_val.clone()
     }
 }
+
+// функция как значение: сама функция, захваченные переменные и объявления её файла
+#[derive(Debug, Clone)]
+pub struct closure {
+    pub func: function,
+    pub env: HashMap<String, Object>,
+    pub home: Rc<RefCell<Definitions>>,
+}
+
+impl closure {
+    pub fn get_type(&self) -> Type {
+        let args = self
+            .func
+            .get_args()
+            .into_iter()
+            .map(|a| a.arg_type)
+            .collect();
+        Type::Function(args, Box::new(self.func.get_return_value()))
+    }
+}
+
+// функции сравниваются по ссылке
+impl PartialEq for closure {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
@@ -1,19 +1,31 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::f32::consts::E;
+use std::rc::Rc;
 use std::thread::scope;
 
 use crate::assignmentNode;
 use crate::binOpNode;
 use crate::expression_node;
+use crate::interpritator::dict::Dict;
+use crate::interpritator::error::{ErrorKind, RuntimeError, error_struct_fields};
+use crate::interpritator::files;
 use crate::interpritator::function::*;
+use crate::interpritator::limits::{self, Budget, Limits};
+use crate::interpritator::math;
 use crate::interpritator::objects::*;
+use crate::interpritator::permissions::{self, Permissions};
+use crate::interpritator::random::{self, Rng};
+use crate::interpritator::strings;
 use crate::interpritator::systemFunctions;
 use crate::interpritator::systemFunctions::*;
 use crate::numberNode;
 use crate::parser::nodes;
 use crate::parser::nodes::expressionNode;
 use crate::parser::nodes::scopeNode;
+use crate::parser::nodes::{armBody, matchNode, patternNode};
 use crate::parser::parser::Arg;
+use crate::parser::parser::Field;
 use crate::parser::parser::Node;
 use crate::parser::parser::Type;
 use crate::variableNode;
@@ -24,68 +36,564 @@ pub enum State {
     Return(Object),
 }
 
+// прежние значения имён, которые перекрыла ветка match; None - имени не было
+type Outer = Vec<(String, Option<Object>)>;
+
+// объявления одного файла; замыкание уносит их с собой, чтобы, вызванное
+// из чужого модуля, видеть функции и типы того места, где его создали
+#[derive(Debug)]
+pub struct Definitions {
+    functions: HashMap<String, function>,
+    structs: HashMap<String, Vec<Field>>,
+    modules: HashMap<String, Rc<RefCell<Interpritator>>>,
+    type_owners: HashMap<String, Rc<RefCell<Interpritator>>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Interpritator {
     variables: HashMap<String, Object>,
-    functions: HashMap<String, function>,
+    defs: Rc<RefCell<Definitions>>,
+    loaded: Rc<RefCell<HashMap<String, Rc<RefCell<Interpritator>>>>>,
+    permissions: Permissions,
+    budget: Rc<RefCell<Budget>>,
+    args: Vec<String>,
+    clock: Rc<RefCell<Clock>>,
+    rng: Rc<RefCell<Rng>>,
 }
 
 impl Interpritator {
     pub fn new() -> Self {
         Self {
             variables: HashMap::new(),
-            functions: HashMap::new(),
+            defs: Rc::new(RefCell::new(Definitions {
+                functions: HashMap::new(),
+                structs: HashMap::from([("Error".to_string(), error_struct_fields())]),
+                modules: HashMap::new(),
+                type_owners: HashMap::new(),
+            })),
+            loaded: Rc::new(RefCell::new(HashMap::new())),
+            permissions: Permissions::default(),
+            budget: Rc::new(RefCell::new(Budget::default())),
+            args: Vec::new(),
+            clock: Rc::new(RefCell::new(Clock::default())),
+            rng: Rc::new(RefCell::new(Rng::from_time())),
         }
     }
 
-    fn mulStr(&self, l: String, r: i32) -> String {
-        let mut res = String::new();
-        for i in 0..r {
-            res = format!("{}{}", res, l);
+    // код пишут студенты, поэтому без явного разрешения встроенные функции
+    // не трогают файлы, окружение и процесс
+    pub fn set_permissions(&mut self, permissions: Permissions) {
+        self.permissions = permissions;
+    }
+
+    // для автоматической проверки: вывод не должен зависеть от времени запуска
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        let (clock, rng) = if deterministic {
+            (Clock::fake(), Rng::new(0))
+        } else {
+            (Clock::real(), Rng::from_time())
+        };
+        self.clock = Rc::new(RefCell::new(clock));
+        self.rng = Rc::new(RefCell::new(rng));
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        *self.rng.borrow_mut() = Rng::new(seed);
+    }
+
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = args;
+    }
+
+    // fn main(args: [string]) -> int вызывается после верхнего уровня,
+    // его результат - код выхода; без main код выхода 0
+    pub fn run_main(&mut self) -> Result<i32, RuntimeError> {
+        let Some(main) = self.defs.borrow().functions.get("main").cloned() else {
+            return Ok(0);
+        };
+        let args = match main.get_args().len() {
+            0 => Vec::new(),
+            _ => vec![self.args_object()],
+        };
+        match self.invoke(&main, args, HashMap::new())? {
+            Object::Int(code) => Ok(code),
+            _ => Ok(0),
+        }
+    }
+
+    fn args_object(&self) -> Object {
+        Object::List(Rc::new(RefCell::new(
+            self.args.iter().cloned().map(Object::String).collect(),
+        )))
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.budget = Rc::new(RefCell::new(Budget::new(limits)));
+    }
+
+    // новая строка или коллекция идёт в счёт лимита памяти
+    fn charge(&self, value: Object) -> Result<Object, RuntimeError> {
+        self.budget
+            .borrow_mut()
+            .allocate(limits::footprint(&value))?;
+        Ok(value)
+    }
+
+    // если размер результата известен заранее, бюджет списывается до того, как
+    // значение построено: иначе repeat("x", 2000000000) успеет занять гигабайты
+    fn reserve(&self, size: Option<usize>) -> Result<(), RuntimeError> {
+        match size {
+            Some(bytes) => self.budget.borrow_mut().allocate(bytes),
+            None => Ok(()),
         }
+    }
+
+    fn settle(&self, size: Option<usize>, value: Object) -> Result<Object, RuntimeError> {
+        match size {
+            Some(_) => Ok(value),
+            None => self.charge(value),
+        }
+    }
 
-        return res;
+    fn authorize(&self, name: &str, args: &[Object]) -> Result<(), RuntimeError> {
+        let Some(capability) = permissions::required(name) else {
+            return Ok(());
+        };
+        let path = match args.first() {
+            Some(Object::String(path)) => Some(path.as_str()),
+            _ => None,
+        };
+        self.permissions.check(capability, path)
     }
 
-    fn type_matches(&mut self, var_type: &Type, val: &Object) -> bool {
+    // константы считаются до запуска программы, парсером
+    pub fn eval_const(&mut self, expr: expressionNode) -> Result<Object, RuntimeError> {
+        self.eval_expr(expr)
+    }
+
+    fn mulStr(&self, l: String, r: i32) -> String {
+        l.repeat(r.max(0) as usize)
+    }
+
+    fn type_matches(&self, var_type: &Type, val: &Object) -> bool {
         match (var_type, val) {
             (Type::Int, Object::Int(_)) => true,
             (Type::Float, Object::Float(_)) => true,
             (Type::String, Object::String(_)) => true,
             (Type::Bool, Object::Bool(_)) => true,
+            (Type::Char, Object::Char(_)) => true,
+            (Type::Long, Object::Long(_)) => true,
+            (Type::Short, Object::Short(_)) => true,
+            (Type::List(elem), Object::List(items)) => items
+                .borrow()
+                .iter()
+                .all(|item| self.type_matches(elem, item)),
+            (Type::Map(key, value), Object::Map(entries)) => entries
+                .borrow()
+                .iter()
+                .all(|(k, v)| self.type_matches(key, k) && self.type_matches(value, v)),
+            (Type::Struct(name), Object::Struct(obj_name, _)) => name == obj_name,
+            (Type::Enum(name), Object::Enum(obj_name, _, _)) => name == obj_name,
+            (Type::Function(_, _), Object::Function(_)) => *var_type == val.get_type(),
+            (Type::Optional(_), Object::None) => true,
+            (Type::Optional(inner), val) => self.type_matches(inner, val),
             (Type::Void, Object::Void) => true,
             _ => false,
         }
     }
 
-    pub fn execute(&mut self, node: Node) -> State {
+    fn default_value(&self, t: &Type) -> Result<Object, RuntimeError> {
+        let value = match t {
+            Type::Int => Object::Int(0),
+            Type::String => Object::String(String::new()),
+            Type::Bool => Object::Bool(false),
+            Type::Float => Object::Float(0.0),
+            Type::Char => Object::Char(('\0')),
+            Type::Long => Object::Long((0)),
+            Type::Short => Object::Short((0)),
+            Type::List(_) => Object::List(Rc::new(RefCell::new(Vec::new()))),
+            Type::Map(_, _) => Object::Map(Rc::new(RefCell::new(Dict::new()))),
+            Type::Struct(name) => {
+                let declared = self
+                    .defs
+                    .borrow()
+                    .structs
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        RuntimeError::new(
+                            ErrorKind::UndefinedName,
+                            format!("unknown struct '{}'", name),
+                        )
+                    })?;
+                let mut fields = Vec::new();
+                for f in declared {
+                    fields.push((f.name.clone(), self.default_value(&f.field_type)?));
+                }
+                Object::Struct(name.clone(), Rc::new(RefCell::new(fields)))
+            }
+            Type::Enum(name) => {
+                return Err(RuntimeError::new(
+                    ErrorKind::UnsupportedOperation,
+                    format!("enum '{}' has no default value", name),
+                ));
+            }
+            Type::Function(_, _) => {
+                return Err(RuntimeError::new(
+                    ErrorKind::UnsupportedOperation,
+                    "function values have no default value".to_string(),
+                ));
+            }
+            Type::Optional(_) => Object::None,
+            Type::Void => Object::Void,
+        };
+        Ok(value)
+    }
+
+    // целое значение для сравнения в диапазонах
+    fn range_key(&self, obj: &Object) -> Option<i128> {
+        match obj {
+            Object::Int(i) => Some(*i as i128),
+            Object::Long(i) => Some(*i),
+            Object::Short(i) => Some(*i as i128),
+            Object::Char(c) => Some(*c as i128),
+            _ => None,
+        }
+    }
+
+    fn match_pattern(
+        &mut self,
+        pattern: &patternNode,
+        value: &Object,
+        bindings: &mut Vec<(String, Object)>,
+    ) -> Result<bool, RuntimeError> {
+        let matched = match pattern {
+            patternNode::Wildcard => true,
+            patternNode::Binding(name) => {
+                bindings.push((name.clone(), value.clone()));
+                true
+            }
+            patternNode::Literal(literal) => self.eval_expr(literal.clone())? == *value,
+            patternNode::Range(start, end, inclusive) => {
+                let start = self.eval_expr(start.clone())?;
+                let end = self.eval_expr(end.clone())?;
+                match (
+                    self.range_key(&start),
+                    self.range_key(&end),
+                    self.range_key(value),
+                ) {
+                    (Some(lo), Some(hi), Some(v)) if start.get_type() == value.get_type() => {
+                        lo <= v && (v < hi || (*inclusive && v == hi))
+                    }
+                    _ => false,
+                }
+            }
+            patternNode::Variant(enum_name, variant, fields) => {
+                let Object::Enum(obj_enum, obj_variant, values) = value else {
+                    return Ok(false);
+                };
+                if obj_enum != enum_name || obj_variant != variant {
+                    return Ok(false);
+                }
+                for (field, value) in fields.iter().zip(values) {
+                    if !self.match_pattern(field, value, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+        };
+        Ok(matched)
+    }
+
+    // имена из образца живут только в своей ветке: вместе с телом возвращаются
+    // прежние значения, которые unbind вернёт на место
+    fn select_arm(&mut self, node: &matchNode) -> Result<(armBody, Outer), RuntimeError> {
+        let subject = self.eval_expr(node.get_subject())?;
+
+        for arm in node.get_arms() {
+            let mut bindings: Vec<(String, Object)> = Vec::new();
+            if self.match_pattern(&arm.get_pattern(), &subject, &mut bindings)? {
+                let mut outer = Vec::new();
+                for (name, value) in bindings {
+                    let previous = self.variables.insert(name.clone(), value);
+                    outer.push((name, previous));
+                }
+                return Ok((arm.get_body(), outer));
+            }
+        }
+
+        Err(RuntimeError::new(
+            ErrorKind::NoMatch,
+            format!("no match arm for value {}", subject),
+        )
+        .at(node.get_position()))
+    }
+
+    fn list_index(&self, index: Object, len: usize) -> Result<usize, RuntimeError> {
+        let i: i128 = match index {
+            Object::Int(i) => i as i128,
+            Object::Long(i) => i,
+            Object::Short(i) => i as i128,
+            _ => {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("list index must be an integer, got {:?}", index.get_type()),
+                ));
+            }
+        };
+
+        if i < 0 || i >= len as i128 {
+            return Err(RuntimeError::new(
+                ErrorKind::IndexOutOfBounds,
+                format!("index {} out of bounds for list of length {}", i, len),
+            ));
+        }
+        Ok(i as usize)
+    }
+
+    fn slice_bound(&self, bound: Object, len: usize) -> Result<usize, RuntimeError> {
+        // граница среза может быть равна длине, какой бы ширины ни было целое
+        let value = match bound {
+            Object::Int(i) => Some(i as i128),
+            Object::Long(i) => Some(i),
+            Object::Short(i) => Some(i as i128),
+            _ => None,
+        };
+        if value == Some(len as i128) {
+            return Ok(len);
+        }
+        self.list_index(bound, len)
+    }
+
+    fn run_scope(&mut self, nodes: Vec<Node>) -> Result<State, RuntimeError> {
+        for node in nodes {
+            if let State::Return(value) = self.execute(node)? {
+                return Ok(State::Return(value));
+            }
+        }
+        Ok(State::Continue)
+    }
+
+    pub fn execute(&mut self, node: Node) -> Result<State, RuntimeError> {
+        self.budget.borrow_mut().step()?;
         match node {
             Node::Assignment(assign) => {
-                let value = self.eval_expr(assign.get_expression());
+                let value = self.eval_expr(assign.get_expression())?;
                 let name = assign.get_variable().get_name();
                 self.variables.insert(name, value);
-                State::Continue
+                Ok(State::Continue)
             }
             Node::ExpressionNode(expr) => {
-                let _ = self.eval_expr(expr);
-                State::Continue
+                let _ = self.eval_expr(expr)?;
+                Ok(State::Continue)
             }
             Node::FunctionNode(func) => {
                 let name = func.get_name();
                 let args = func.get_args();
                 let scope = func.get_scope();
                 let ret_val = func.get_ret_val();
-                self.functions
+                self.defs
+                    .borrow_mut()
+                    .functions
                     .insert(name.clone(), function::new(name, scope, args, ret_val));
-                State::Continue
+                Ok(State::Continue)
             }
             Node::ReturnNode(ret_node) => {
-                let value = self.eval_expr(ret_node.get_return_value());
-                State::Return((value))
+                let value = self.eval_expr(ret_node.get_return_value())?;
+                Ok(State::Return((value)))
+            }
+            Node::IndexAssignment(assign) => {
+                let target = self.eval_expr(assign.get_target())?;
+                let index = self.eval_expr(assign.get_index())?;
+                let value = self.eval_expr(assign.get_value())?;
+
+                match target {
+                    Object::List(items) => {
+                        let len = items.borrow().len();
+                        let i = self
+                            .list_index(index, len)
+                            .map_err(|e| e.at(assign.get_position()))?;
+                        items.borrow_mut()[i] = value;
+                    }
+                    Object::Map(entries) => {
+                        let mut entries = entries.borrow_mut();
+                        if !entries.contains(&index) {
+                            self.budget
+                                .borrow_mut()
+                                .allocate(2 * size_of::<Object>())
+                                .map_err(|e| e.at(assign.get_position()))?;
+                        }
+                        entries.insert(index, value);
+                    }
+                    _ => {
+                        return Err(RuntimeError::new(
+                            ErrorKind::UnsupportedOperation,
+                            format!("cannot index into {:?}", target.get_type()),
+                        )
+                        .at(assign.get_position()));
+                    }
+                }
+                Ok(State::Continue)
+            }
+            Node::StructNode(struct_node) => {
+                self.defs
+                    .borrow_mut()
+                    .structs
+                    .insert(struct_node.get_name(), struct_node.get_fields());
+                Ok(State::Continue)
+            }
+            Node::ImplNode(methods) => {
+                for func in methods {
+                    self.execute(Node::FunctionNode(func))?;
+                }
+                Ok(State::Continue)
+            }
+            Node::FieldAssignment(assign) => {
+                let target = self.eval_expr(assign.get_target())?;
+                let value = self.eval_expr(assign.get_value())?;
+                let field = assign.get_field();
+
+                let Object::Struct(name, fields) = target else {
+                    return Err(RuntimeError::new(
+                        ErrorKind::UnsupportedOperation,
+                        format!("cannot set field '{}' on {:?}", field, target.get_type()),
+                    )
+                    .at(assign.get_position()));
+                };
+                let mut fields = fields.borrow_mut();
+                match fields.iter_mut().find(|(f, _)| *f == field) {
+                    Some((_, old)) => *old = value,
+                    None => {
+                        return Err(RuntimeError::new(
+                            ErrorKind::UndefinedName,
+                            format!("struct '{}' has no field '{}'", name, field),
+                        )
+                        .at(assign.get_position()));
+                    }
+                }
+                Ok(State::Continue)
+            }
+            Node::Enum => Ok(State::Continue),
+            Node::Match(match_node) => {
+                let (body, outer) = self.select_arm(&match_node)?;
+                let state = match body {
+                    armBody::Scope(scope) => self.run_scope(scope.get_nodes()),
+                    armBody::Expression(expr) => self.eval_expr(expr).map(|_| State::Continue),
+                };
+                self.unbind(outer);
+                state
             }
+            Node::For(for_node) => {
+                let iterable = self.eval_expr(for_node.get_iterable())?;
+                let items: Vec<Object> = match iterable {
+                    Object::List(items) => items.borrow().clone(),
+                    Object::Map(entries) => entries.borrow().keys(),
+                    _ => {
+                        return Err(RuntimeError::new(
+                            ErrorKind::UnsupportedOperation,
+                            format!("cannot iterate over {:?}", iterable.get_type()),
+                        ));
+                    }
+                };
+
+                let var_name = for_node.get_var_name();
+                let body = for_node.get_body().get_nodes();
+                //после цикла имя снова значит то, что значило до него
+                let outer = self.variables.get(&var_name).cloned();
+                let mut state = Ok(State::Continue);
+                for item in items {
+                    self.variables.insert(var_name.clone(), item);
+                    state = self.run_scope(body.clone());
+                    if !matches!(state, Ok(State::Continue)) {
+                        break;
+                    }
+                }
+                self.rebind(&var_name, outer);
+                state
+            }
+            Node::IfLet(if_let) => match self.eval_expr(if_let.get_value())? {
+                Object::None => match if_let.get_else_body() {
+                    Some(else_body) => self.run_scope(else_body.get_nodes()),
+                    None => Ok(State::Continue),
+                },
+                value => {
+                    //развёрнутое значение видно только внутри блока, как и в парсере
+                    let name = if_let.get_name();
+                    let outer = self.variables.insert(name.clone(), value);
+                    let state = self.run_scope(if_let.get_body().get_nodes());
+                    self.rebind(&name, outer);
+                    state
+                }
+            },
+            Node::Import(import) => {
+                let path = import.get_path();
+                let cached = self.loaded.borrow().get(&path).cloned();
+                //верхний уровень модуля выполняется один раз на программу
+                let module = match cached {
+                    Some(module) => module,
+                    None => {
+                        let mut instance = Interpritator::new();
+                        instance.loaded = self.loaded.clone();
+                        instance.permissions = self.permissions.clone();
+                        instance.budget = self.budget.clone();
+                        instance.args = self.args.clone();
+                        instance.clock = self.clock.clone();
+                        instance.rng = self.rng.clone();
+                        for node in import.get_nodes().iter() {
+                            instance.execute(node.clone())?;
+                        }
+                        let module = Rc::new(RefCell::new(instance));
+                        self.loaded.borrow_mut().insert(path, module.clone());
+                        module
+                    }
+                };
+
+                let mut defs = self.defs.borrow_mut();
+                for type_name in import.get_types() {
+                    let fields = module
+                        .borrow()
+                        .defs
+                        .borrow()
+                        .structs
+                        .get(&type_name)
+                        .cloned();
+                    if let Some(fields) = fields {
+                        defs.structs.insert(type_name.clone(), fields);
+                    }
+                    defs.type_owners.insert(type_name, module.clone());
+                }
+                defs.modules.insert(import.get_alias(), module);
+                Ok(State::Continue)
+            }
+            Node::Try(try_node) => match self.run_scope(try_node.get_body().get_nodes()) {
+                Ok(state) => Ok(state),
+                // превышенный лимит не ловится, иначе программа продолжит крутиться
+                Err(error) if error.is_fatal() => Err(error),
+                Err(error) => {
+                    let name = try_node.get_error_name();
+                    let outer = self.variables.insert(name.clone(), error.to_object());
+                    let state = self.run_scope(try_node.get_handler().get_nodes());
+                    self.rebind(&name, outer);
+                    state
+                }
+            },
         }
     }
 
+    fn unbind(&mut self, outer: Outer) {
+        for (name, value) in outer.into_iter().rev() {
+            self.rebind(&name, value);
+        }
+    }
+
+    fn rebind(&mut self, name: &str, outer: Option<Object>) {
+        match outer {
+            Some(value) => self.variables.insert(name.to_string(), value),
+            None => self.variables.remove(name),
+        };
+    }
+
     fn get_clone_node(&self, scope: &scopeNode) -> Vec<Node> {
         let mut nodes: Vec<Node> = Vec::new();
         for node in scope.get_nodes() {
@@ -94,266 +602,733 @@ impl Interpritator {
         nodes
     }
 
-    fn eval_expr(&mut self, expr: expressionNode) -> Object {
-        match expr {
-            expressionNode::DefaultValue(t) => match t {
-                Type::Int => Object::Int(0),
-                Type::String => Object::String(String::new()),
-                Type::Bool => Object::Bool(false),
-                Type::Float => Object::Float(0.0),
-                Type::Char => Object::Char(('\0')),
-                Type::Long => Object::Long((0)),
-                Type::Short => Object::Short((0)),
-                Type::Void => Object::Void,
-            },
+    fn eval_expr(&mut self, expr: expressionNode) -> Result<Object, RuntimeError> {
+        self.budget.borrow_mut().step()?;
+        let fresh = matches!(
+            expr,
+            expressionNode::Interpolation(_)
+                | expressionNode::ListLiteral(_)
+                | expressionNode::MapLiteral(_)
+                | expressionNode::Slice(_)
+        );
+        let value = match expr {
+            expressionNode::DefaultValue(t) => self.default_value(&t)?,
             expressionNode::Number(num_node) => Object::Int((num_node.get_value())),
             expressionNode::LongExpression(long_num) => Object::Long((long_num)),
+            expressionNode::ShortExpression(short_num) => Object::Short(short_num),
             expressionNode::FloatExpression(f_num) => Object::Float((f_num)),
             expressionNode::CharLiteral(char_node) => Object::Char((char_node)),
             expressionNode::StringLiteral(str_node) => Object::String((str_node)),
+            expressionNode::Interpolation(parts) => {
+                let mut res = String::new();
+                for part in parts {
+                    res.push_str(&self.eval_expr(part)?.to_string());
+                }
+                Object::String(res)
+            }
             expressionNode::Bool(bool_node) => Object::Bool((bool_node)),
+            expressionNode::None => Object::None,
             expressionNode::Variable(var_node) => {
                 let name = var_node.get_name();
-                self.variables
+                self.variables.get(&name).cloned().ok_or_else(|| {
+                    RuntimeError::new(
+                        ErrorKind::UndefinedName,
+                        format!("var '{}' not exits", name),
+                    )
+                })?
+            }
+            expressionNode::ListLiteral(items) => {
+                let mut values: Vec<Object> = Vec::new();
+                for item in items {
+                    values.push(self.eval_expr(item)?);
+                }
+                Object::List(Rc::new(RefCell::new(values)))
+            }
+            expressionNode::Index(index) => {
+                let target = self.eval_expr(index.get_target())?;
+                let i = self.eval_expr(index.get_index())?;
+
+                match target {
+                    Object::List(items) => {
+                        let items = items.borrow();
+                        let i = self
+                            .list_index(i, items.len())
+                            .map_err(|e| e.at(index.get_position()))?;
+                        items[i].clone()
+                    }
+                    Object::Map(entries) => entries.borrow().get(&i).cloned().ok_or_else(|| {
+                        RuntimeError::new(
+                            ErrorKind::KeyNotFound,
+                            format!("key {} not found in map", i),
+                        )
+                        .at(index.get_position())
+                    })?,
+                    _ => {
+                        return Err(RuntimeError::new(
+                            ErrorKind::UnsupportedOperation,
+                            format!("cannot index into {:?}", target.get_type()),
+                        )
+                        .at(index.get_position()));
+                    }
+                }
+            }
+            expressionNode::StructLiteral(literal) => {
+                let name = literal.get_name();
+                let mut values: Vec<(String, Object)> = Vec::new();
+                for (field, value) in literal.get_fields() {
+                    values.push((field, self.eval_expr(value)?));
+                }
+
+                //поля храним в порядке объявления
+                let declared = self
+                    .defs
+                    .borrow()
+                    .structs
                     .get(&name)
                     .cloned()
-                    .unwrap_or_else(|| panic!("var '{}' not exits", name))
+                    .ok_or_else(|| {
+                        RuntimeError::new(
+                            ErrorKind::UndefinedName,
+                            format!("unknown struct '{}'", name),
+                        )
+                    })?;
+                let mut fields = Vec::new();
+                for f in declared {
+                    let value = values
+                        .iter()
+                        .find(|(n, _)| *n == f.name)
+                        .map(|(_, v)| v.clone())
+                        .ok_or_else(|| {
+                            RuntimeError::new(
+                                ErrorKind::UndefinedName,
+                                format!("missing field '{}'", f.name),
+                            )
+                        })?;
+                    fields.push((f.name.clone(), value));
+                }
+                Object::Struct(name, Rc::new(RefCell::new(fields)))
             }
-            expressionNode::BinOp(boxed_op) => {
-                let left: Object = self.eval_expr(boxed_op.get_left());
-                let right = self.eval_expr(boxed_op.get_right());
-                let op = boxed_op.get_op();
+            expressionNode::Field(field) => {
+                let target = self.eval_expr(field.get_target())?;
+                let name = field.get_field();
 
-                match (left, right) {
-                    (Object::Int(l), Object::Int(r)) => match op {
-                        '+' => Object::Int(l + r),
-                        '-' => Object::Int(l - r),
-                        '*' => Object::Int(l * r),
-                        '/' => {
-                            if r == 0 {
-                                panic!("ne deli na 0!");
-                            }
-                            Object::Int(l / r)
-                        }
-                        _ => panic!("don`t support operator : '{}'", op),
-                    },
-                    (Object::String(l), Object::String(r)) => match op {
-                        '+' => Object::String(l + &r),
-                        _ => panic!("nelzia mul, delet i misuc stroki!"),
-                    },
-                    (Object::String(l), Object::Int(r)) => match op {
-                        '*' => Object::String(self.mulStr(l, r)),
-                        _ => panic!("nelzia delet i misuc stroki"),
-                    },
-                    _ => panic!("ia ne znayu che zdeci napicati"),
+                let Object::Struct(struct_name, fields) = target else {
+                    return Err(RuntimeError::new(
+                        ErrorKind::UnsupportedOperation,
+                        format!("cannot read field '{}' of {:?}", name, target.get_type()),
+                    )
+                    .at(field.get_position()));
+                };
+                fields
+                    .borrow()
+                    .iter()
+                    .find(|(f, _)| *f == name)
+                    .map(|(_, value)| value.clone())
+                    .ok_or_else(|| {
+                        RuntimeError::new(
+                            ErrorKind::UndefinedName,
+                            format!("struct '{}' has no field '{}'", struct_name, name),
+                        )
+                        .at(field.get_position())
+                    })?
+            }
+            expressionNode::MethodCall(call) => {
+                let target = self.eval_expr(call.get_target())?;
+                let type_name = match &target {
+                    Object::Struct(name, _) | Object::Enum(name, _, _) => name.clone(),
+                    _ => {
+                        return Err(RuntimeError::new(
+                            ErrorKind::UnsupportedOperation,
+                            format!(
+                                "cannot call method '{}' on {:?}",
+                                call.get_method(),
+                                target.get_type()
+                            ),
+                        )
+                        .at(call.get_position()));
+                    }
+                };
+
+                let method = format!("{}.{}", type_name, call.get_method());
+                if !self.has_function(&method) {
+                    return Err(RuntimeError::new(
+                        ErrorKind::UndefinedName,
+                        format!("type '{}' has no method '{}'", type_name, call.get_method()),
+                    )
+                    .at(call.get_position()));
                 }
+
+                let mut evaled_args = Vec::new();
+                for arg in call.get_arguments() {
+                    evaled_args.push(self.eval_expr(arg)?);
+                }
+                self.call_function(method, evaled_args, Some(target))
+                    .map_err(|e| e.at(call.get_position()))?
             }
-            expressionNode::FunctionCall(call) => {
-                let name = call.get_function_name();
+            expressionNode::Lambda(func) => Object::Function(Rc::new(closure {
+                func: function::new(
+                    func.get_name(),
+                    func.get_scope(),
+                    func.get_args(),
+                    func.get_ret_val(),
+                ),
+                env: self.variables.clone(),
+                home: self.defs.clone(),
+            })),
+            expressionNode::FunctionRef(name) => {
+                let func = self
+                    .defs
+                    .borrow()
+                    .functions
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        RuntimeError::new(
+                            ErrorKind::UndefinedName,
+                            format!("function '{}' not exits", name),
+                        )
+                    })?;
+                Object::Function(Rc::new(closure {
+                    func,
+                    env: HashMap::new(),
+                    home: self.defs.clone(),
+                }))
+            }
+            expressionNode::CallValue(call) => {
+                let callee = self.eval_expr(call.get_callee())?;
+                let Object::Function(c) = callee else {
+                    return Err(RuntimeError::new(
+                        ErrorKind::UnsupportedOperation,
+                        format!("cannot call value of type {:?}", callee.get_type()),
+                    )
+                    .at(call.get_position()));
+                };
 
-                if name == "println" {
-                    let mut args_for_println: Vec<Object> = Vec::new();
-                    for arg in call.get_arguments() {
-                        args_for_println.push(self.eval_expr(arg));
+                let mut evaled_args = Vec::new();
+                for arg in call.get_arguments() {
+                    evaled_args.push(self.eval_expr(arg)?);
+                }
+                self.call_closure(&c, evaled_args)
+                    .map_err(|e| e.at(call.get_position()))?
+            }
+            expressionNode::EnumLiteral(literal) => {
+                let mut values: Vec<Object> = Vec::new();
+                for arg in literal.get_arguments() {
+                    values.push(self.eval_expr(arg)?);
+                }
+                Object::Enum(literal.get_enum_name(), literal.get_variant(), values)
+            }
+            expressionNode::Match(match_node) => {
+                let (body, outer) = self.select_arm(&match_node)?;
+                let value = match body {
+                    armBody::Expression(expr) => self.eval_expr(expr),
+                    armBody::Scope(_) => Err(RuntimeError::new(
+                        ErrorKind::UnsupportedOperation,
+                        "block arms are not allowed in match expressions".to_string(),
+                    )
+                    .at(match_node.get_position())),
+                };
+                self.unbind(outer);
+                value?
+            }
+            expressionNode::MapLiteral(entries) => {
+                let mut values = Dict::new();
+                for (key, value) in entries {
+                    let key = self.eval_expr(key)?;
+                    let value = self.eval_expr(value)?;
+                    values.insert(key, value);
+                }
+                Object::Map(Rc::new(RefCell::new(values)))
+            }
+            expressionNode::Unary(unary) => {
+                let value = self.eval_expr(unary.get_value())?;
+                Self::unary_op(value, &unary.get_op()).map_err(|e| e.at(unary.get_position()))?
+            }
+            expressionNode::Cast(cast) => {
+                let value = self.eval_expr(cast.get_value())?;
+                Self::cast(value, &cast.get_target()).map_err(|e| e.at(cast.get_position()))?
+            }
+            expressionNode::Slice(slice) => {
+                let target = self.eval_expr(slice.get_target())?;
+                let items = target.as_list().ok_or_else(|| {
+                    RuntimeError::new(
+                        ErrorKind::UnsupportedOperation,
+                        format!("cannot slice {:?}", target.get_type()),
+                    )
+                    .at(slice.get_position())
+                })?;
+                let len = items.borrow().len();
+
+                let start = match slice.get_start() {
+                    Some(start) => {
+                        let start = self.eval_expr(start)?;
+                        self.slice_bound(start, len)
+                            .map_err(|e| e.at(slice.get_position()))?
                     }
-                    systemFunctions::println(args_for_println);
-                    return Object::Void;
-                } else if name == "print" {
-                    let mut args_for_println: Vec<Object> = Vec::new();
-                    for arg in call.get_arguments() {
-                        args_for_println.push(self.eval_expr(arg));
+                    None => 0,
+                };
+                let end = match slice.get_end() {
+                    Some(end) => {
+                        let end = self.eval_expr(end)?;
+                        self.slice_bound(end, len)
+                            .map_err(|e| e.at(slice.get_position()))?
                     }
-                    systemFunctions::print(args_for_println);
-                    return Object::Void;
-                } else if name == "scan" {
-                    return systemFunctions::scan();
-                } else if name == "quit" {
-                    systemFunctions::quit();
+                    None => len,
+                };
+                if start > end {
+                    return Err(RuntimeError::new(
+                        ErrorKind::IndexOutOfBounds,
+                        format!("slice start {} is greater than end {}", start, end),
+                    )
+                    .at(slice.get_position()));
                 }
 
-                let args = call.get_arguments();
+                let part = items.borrow()[start..end].to_vec();
+                Object::List(Rc::new(RefCell::new(part)))
+            }
+            expressionNode::BinOp(boxed_op) => {
+                let left: Object = self.eval_expr(boxed_op.get_left())?;
+                let op = boxed_op.get_op();
 
-                let mut evaled_args = Vec::new();
+                //правая часть ?? считается только для none
+                if op == "??" {
+                    return match left {
+                        Object::None => self.eval_expr(boxed_op.get_right()),
+                        value => Ok(value),
+                    };
+                }
+                let right = self.eval_expr(boxed_op.get_right())?;
 
-                for arg in &args {
-                    evaled_args.push(self.eval_expr(arg.clone()));
+                let size = limits::binop_size(&left, &right, &op);
+                self.reserve(size)
+                    .and_then(|_| self.bin_op(left, right, &op))
+                    .and_then(|v| self.settle(size, v))
+                    .map_err(|e| e.at(boxed_op.get_position()))?
+            }
+            expressionNode::FunctionCall(call) => {
+                let name = call.get_function_name();
+
+                let mut evaled_args = Vec::new();
+                for arg in call.get_arguments() {
+                    evaled_args.push(self.eval_expr(arg)?);
                 }
-                let temp = self.variables.clone();
-
-                let scope_nodes = if let Some(c_fn) = self.functions.get(&name) {
-                    let f_args = c_fn.get_args().clone();
-                    let scope = c_fn.get_scope();
-
-                    for (i, value) in evaled_args.iter().enumerate() {
-                        match &args[i] {
-                            expressionNode::StringLiteral(_) => {
-                                if f_args[i].arg_type == Type::String {
-                                    let name = f_args[i].name.clone();
-                                    scope.add_variable(
-                                        name,
-                                        Object::String(value.as_string().unwrap().clone()),
-                                    );
-                                } else {
-                                    panic!("error with types");
-                                }
-                            }
-                            expressionNode::Number(_) => {
-                                if f_args[i].arg_type == Type::Int {
-                                    let name = f_args[i].name.clone();
-                                    scope.add_variable(
-                                        name,
-                                        Object::Int(value.as_int().unwrap().clone()),
-                                    );
-                                } else if f_args[i].arg_type == Type::Float {
-                                    let name = f_args[i].name.clone();
-                                    scope.add_variable(
-                                        name,
-                                        Object::Float(value.as_float().unwrap().clone()),
-                                    );
-                                } else {
-                                    panic!("error with types");
-                                }
-                            }
-                            expressionNode::BinOp(_) => {
-                                let name = f_args[i].name.clone();
-
-                                match &value {
-                                    Object::Int(v) => {
-                                        if f_args[i].arg_type != Type::Int {
-                                            panic!(
-                                                "type mismatch: expected {:?}, got Int",
-                                                f_args[i].arg_type
-                                            );
-                                        }
-                                        scope.add_variable(name, Object::Int(*v));
-                                    }
-
-                                    Object::String(s) => {
-                                        if f_args[i].arg_type != Type::String {
-                                            panic!(
-                                                "type mismatch: expected {:?}, got String",
-                                                f_args[i].arg_type
-                                            );
-                                        }
-                                        scope.add_variable(name, Object::String(s.clone()));
-                                    }
-
-                                    _ => panic!(
-                                        "BinOp result must be Int or String, got {:?}",
-                                        value
-                                    ),
-                                }
-                            }
-                            expressionNode::Variable(var) => {
-                                if f_args[i].arg_type == var.get_type() {
-                                    let name = f_args[i].name.clone();
-
-                                    match var.get_type() {
-                                        Type::Short => {
-                                            scope.add_variable(
-                                                name,
-                                                Object::Short(
-                                                    value.as_short().expect("need Int value"),
-                                                ),
-                                            );
-                                        }
-                                        Type::Long => {
-                                            scope.add_variable(
-                                                name,
-                                                Object::Long(
-                                                    value.as_long().expect("need Int value"),
-                                                ),
-                                            );
-                                        }
-                                        Type::Char => {
-                                            scope.add_variable(
-                                                name,
-                                                Object::Char(
-                                                    value.as_char().expect("need Int value"),
-                                                ),
-                                            );
-                                        }
-                                        Type::Int => {
-                                            scope.add_variable(
-                                                name,
-                                                Object::Int(
-                                                    value.as_int().expect("need Int value"),
-                                                ),
-                                            );
-                                        }
-                                        Type::String => {
-                                            scope.add_variable(
-                                                name,
-                                                Object::String(
-                                                    value.as_string().expect("need String value"),
-                                                ),
-                                            );
-                                        }
-                                        Type::Bool => {
-                                            scope.add_variable(
-                                                name,
-                                                Object::Bool(
-                                                    value.as_bool().expect("need Bool value"),
-                                                ),
-                                            );
-                                        }
-                                        Type::Float => {
-                                            scope.add_variable(
-                                                name,
-                                                Object::Float(
-                                                    value.as_float().expect("need Float value"),
-                                                ),
-                                            );
-                                        }
-                                        Type::Void => {
-                                            panic!("Cannot assign variable of type Void");
-                                        }
-                                    }
-                                } else {
-                                    panic!(
-                                        "Type mismatch in function argument '{}': expected {:?}, got {:?}",
-                                        f_args[i].name,
-                                        f_args[i].arg_type,
-                                        var.get_type()
-                                    );
-                                }
-                            }
-
-                            _ => panic!("govvvmo"),
+
+                let result = if name == "println" {
+                    systemFunctions::println(evaled_args);
+                    Ok(Object::Void)
+                } else if name == "print" {
+                    systemFunctions::print(evaled_args);
+                    Ok(Object::Void)
+                } else if name == "scan" {
+                    systemFunctions::scan()
+                } else if name == "quit" {
+                    self.authorize(&name, &evaled_args)
+                        .and_then(|_| systemFunctions::quit(evaled_args))
+                } else if name == "env" {
+                    self.authorize(&name, &evaled_args)
+                        .and_then(|_| systemFunctions::env(evaled_args))
+                } else if name == "args" {
+                    match evaled_args.as_slice() {
+                        [] => self.charge(self.args_object()),
+                        _ => Err(wrong_args("args", "no arguments", &evaled_args)),
+                    }
+                } else if matches!(
+                    name.as_str(),
+                    "len" | "push" | "pop" | "has" | "remove" | "keys" | "values"
+                ) && !self.builtin_shadowed(&name)
+                {
+                    match name.as_str() {
+                        "len" => systemFunctions::len(evaled_args),
+                        "push" => self
+                            .budget
+                            .borrow_mut()
+                            .allocate(size_of::<Object>())
+                            .and_then(|_| systemFunctions::push(evaled_args)),
+                        "pop" => systemFunctions::pop(evaled_args),
+                        "has" => systemFunctions::has(evaled_args),
+                        "remove" => systemFunctions::remove(evaled_args),
+                        "keys" => systemFunctions::keys(evaled_args).and_then(|v| self.charge(v)),
+                        _ => systemFunctions::values(evaled_args).and_then(|v| self.charge(v)),
+                    }
+                } else if strings::is_string_function(&name) && !self.builtin_shadowed(&name) {
+                    let size = limits::builtin_size(&name, &evaled_args);
+                    self.reserve(size)
+                        .and_then(|_| strings::call(&name, evaled_args))
+                        .and_then(|v| self.settle(size, v))
+                } else if math::is_math_function(&name) && !self.builtin_shadowed(&name) {
+                    math::call(&name, evaled_args)
+                } else if files::is_file_function(&name) && !self.builtin_shadowed(&name) {
+                    // размер файла смотрим только после проверки прав
+                    self.authorize(&name, &evaled_args).and_then(|_| {
+                        let size = limits::builtin_size(&name, &evaled_args);
+                        self.reserve(size)
+                            .and_then(|_| files::call(&name, evaled_args))
+                            .and_then(|v| self.settle(size, v))
+                    })
+                } else if systemFunctions::is_time_function(&name) && !self.builtin_shadowed(&name)
+                {
+                    match name.as_str() {
+                        "now_ms" => systemFunctions::now_ms(&self.clock.borrow(), evaled_args),
+                        "clock" => systemFunctions::clock(&self.clock.borrow(), evaled_args),
+                        "sleep" => {
+                            let remaining = self.budget.borrow_mut().remaining();
+                            systemFunctions::sleep(
+                                &mut self.clock.borrow_mut(),
+                                remaining,
+                                evaled_args,
+                            )
+                            .and_then(|v| self.budget.borrow_mut().check_time().map(|_| v))
                         }
+                        _ => systemFunctions::format_time(evaled_args).and_then(|v| self.charge(v)),
                     }
-
-                    self.variables = scope.get_variables();
-                    Some(scope.get_nodes().clone())
+                } else if random::is_random_function(&name) && !self.builtin_shadowed(&name) {
+                    random::call(&mut self.rng.borrow_mut(), &name, evaled_args)
                 } else {
-                    None
+                    self.call_named(name, evaled_args)
                 };
+                result.map_err(|e| e.at(call.get_position()))?
+            }
+        };
+        if fresh {
+            return self.charge(value);
+        }
+        Ok(value)
+    }
 
-                if let Some(scope_nodes) = scope_nodes {
-                    let mut return_value = Object::Void;
+    fn bin_op(&self, left: Object, right: Object, op: &str) -> Result<Object, RuntimeError> {
+        match op {
+            "==" => return Ok(Object::Bool(left == right)),
+            "!=" => return Ok(Object::Bool(left != right)),
+            _ => {}
+        }
 
-                    for node in scope_nodes {
-                        match self.execute(node) {
-                            State::Continue => continue,
-                            State::Return(value) => {
-                                return_value = value;
-                                break;
-                            }
-                        }
-                    }
+        let unsupported = RuntimeError::new(
+            ErrorKind::UnsupportedOperation,
+            format!(
+                "don`t support operator '{}' for {:?} and {:?}",
+                op,
+                left.get_type(),
+                right.get_type()
+            ),
+        );
+        let value = match (left, right) {
+            (Object::Int(l), Object::Int(r)) => {
+                Object::Int(Self::checked(l, r, op, "int", unsupported)?)
+            }
+            (Object::Long(l), Object::Long(r)) => {
+                Object::Long(Self::checked(l, r, op, "long", unsupported)?)
+            }
+            (Object::Short(l), Object::Short(r)) => {
+                Object::Short(Self::checked(l, r, op, "short", unsupported)?)
+            }
+            (Object::Float(l), Object::Float(r)) => match op {
+                "+" => Object::Float(l + r),
+                "-" => Object::Float(l - r),
+                "*" => Object::Float(l * r),
+                "/" => Object::Float(l / r),
+                "%" => Object::Float(l % r),
+                "**" => Object::Float(l.powf(r)),
+                _ => return Err(unsupported),
+            },
+            (Object::String(l), Object::String(r)) => match op {
+                "+" => Object::String(l + &r),
+                _ => return Err(unsupported),
+            },
+            (Object::String(l), Object::Int(r)) => match op {
+                "*" => Object::String(self.mulStr(l, r)),
+                _ => return Err(unsupported),
+            },
+            _ => return Err(unsupported),
+        };
+        Ok(value)
+    }
 
-                    self.variables = temp;
+    // сужение проверяется: 300 as short это ошибка, а не 44
+    fn cast(value: Object, target: &Type) -> Result<Object, RuntimeError> {
+        let out_of_range = |value: &dyn std::fmt::Display| {
+            RuntimeError::new(
+                ErrorKind::Overflow,
+                format!("{} is out of range for {:?}", value, target),
+            )
+        };
 
-                    if let Some(c_fn) = self.functions.get(&name) {
-                        if return_value.get_type() == c_fn.get_return_value() {
-                            return return_value;
-                        } else {
-                            panic!("func return")
-                        }
-                    }
+        if let Type::Optional(inner) = target {
+            return match value {
+                Object::None => Ok(Object::None),
+                value => Self::cast(value, inner),
+            };
+        }
+        if value.get_type() == *target {
+            return Ok(value);
+        }
+
+        let whole: i128 = match value {
+            Object::Int(v) => v as i128,
+            Object::Long(v) => v,
+            Object::Short(v) => v as i128,
+            Object::Char(c) => c as i128,
+            Object::Bool(b) => b as i128,
+            Object::Float(f) => {
+                if *target == Type::Float {
+                    return Ok(Object::Float(f));
                 }
-                return Object::Void;
+                if !f.is_finite() || f.trunc() < i128::MIN as f32 || f.trunc() > i128::MAX as f32 {
+                    return Err(out_of_range(&f));
+                }
+                f.trunc() as i128
+            }
+            value => {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("cannot cast {:?} to {:?}", value.get_type(), target),
+                ));
+            }
+        };
+
+        let cast = match target {
+            Type::Int => i32::try_from(whole).ok().map(Object::Int),
+            Type::Long => Some(Object::Long(whole)),
+            Type::Short => i8::try_from(whole).ok().map(Object::Short),
+            Type::Float => Some(Object::Float(whole as f32)),
+            Type::Char => u32::try_from(whole)
+                .ok()
+                .and_then(char::from_u32)
+                .map(Object::Char),
+            _ => {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("cannot cast {} to {:?}", whole, target),
+                ));
+            }
+        };
+        cast.ok_or_else(|| out_of_range(&whole))
+    }
+
+    // целая арифметика без тихого переполнения: считаем в i128 и сужаем обратно
+    fn checked<T: TryFrom<i128> + Into<i128>>(
+        l: T,
+        r: T,
+        op: &str,
+        type_name: &str,
+        unsupported: RuntimeError,
+    ) -> Result<T, RuntimeError> {
+        let (l, r): (i128, i128) = (l.into(), r.into());
+        let bits = std::mem::size_of::<T>() as u32 * 8;
+        let overflow = || {
+            RuntimeError::new(
+                ErrorKind::Overflow,
+                format!("{} {} {} overflows {}", l, op, r, type_name),
+            )
+        };
+        let division_by_zero =
+            || RuntimeError::new(ErrorKind::DivisionByZero, "ne deli na 0!".to_string());
+
+        let result = match op {
+            "+" => l.checked_add(r),
+            "-" => l.checked_sub(r),
+            "*" => l.checked_mul(r),
+            "/" if r == 0 => return Err(division_by_zero()),
+            "/" => l.checked_div(r),
+            "%" if r == 0 => return Err(division_by_zero()),
+            "%" => l.checked_rem(r),
+            "**" if r < 0 => {
+                return Err(RuntimeError::new(
+                    ErrorKind::UnsupportedOperation,
+                    format!("negative exponent {} for {}", r, type_name),
+                ));
+            }
+            "**" => u32::try_from(r).ok().and_then(|r| l.checked_pow(r)),
+            "&" => Some(l & r),
+            "|" => Some(l | r),
+            "^" => Some(l ^ r),
+            "<<" | ">>" if r < 0 || r >= bits as i128 => {
+                return Err(RuntimeError::new(
+                    ErrorKind::Overflow,
+                    format!("shift by {} is out of range for {}", r, type_name),
+                ));
+            }
+            // выдвинутые биты теряются, как в C и Rust
+            "<<" => {
+                let unused = 128 - bits;
+                Some(((l << r) << unused) >> unused)
+            }
+            ">>" => Some(l >> r),
+            _ => return Err(unsupported),
+        };
+        result
+            .and_then(|value| T::try_from(value).ok())
+            .ok_or_else(overflow)
+    }
+
+    fn unary_op(value: Object, op: &str) -> Result<Object, RuntimeError> {
+        let overflow = |value: &dyn std::fmt::Display, type_name: &str| {
+            RuntimeError::new(
+                ErrorKind::Overflow,
+                format!("-({}) overflows {}", value, type_name),
+            )
+        };
+        let value = match (op, value) {
+            ("-", Object::Int(v)) => {
+                Object::Int(v.checked_neg().ok_or_else(|| overflow(&v, "int"))?)
             }
+            ("-", Object::Long(v)) => {
+                Object::Long(v.checked_neg().ok_or_else(|| overflow(&v, "long"))?)
+            }
+            ("-", Object::Short(v)) => {
+                Object::Short(v.checked_neg().ok_or_else(|| overflow(&v, "short"))?)
+            }
+            ("-", Object::Float(v)) => Object::Float(-v),
+            (
+                "+",
+                value @ (Object::Int(_) | Object::Long(_) | Object::Short(_) | Object::Float(_)),
+            ) => value,
+            ("!", Object::Bool(v)) => Object::Bool(!v),
+            ("~", Object::Int(v)) => Object::Int(!v),
+            ("~", Object::Long(v)) => Object::Long(!v),
+            ("~", Object::Short(v)) => Object::Short(!v),
+            (_, value) => {
+                return Err(RuntimeError::new(
+                    ErrorKind::UnsupportedOperation,
+                    format!("don`t support operator '{}' for {:?}", op, value.get_type()),
+                ));
+            }
+        };
+        Ok(value)
+    }
+
+    fn call_function(
+        &mut self,
+        name: String,
+        evaled_args: Vec<Object>,
+        receiver: Option<Object>,
+    ) -> Result<Object, RuntimeError> {
+        let Some(c_fn) = self.defs.borrow().functions.get(&name).cloned() else {
+            if let Some((module, name)) = self.foreign(&name) {
+                return module
+                    .borrow_mut()
+                    .call_function(name, evaled_args, receiver);
+            }
+            return Err(RuntimeError::new(
+                ErrorKind::UndefinedName,
+                format!("function '{}' is not defined", name),
+            ));
+        };
+
+        let mut env: HashMap<String, Object> = HashMap::new();
+        if let Some(receiver) = receiver {
+            env.insert("self".to_string(), receiver);
+        }
+        self.invoke(&c_fn, evaled_args, env)
+    }
+
+    // встроенную закрывает только то, что можно вызвать: своя функция или переменная-функция
+    fn builtin_shadowed(&self, name: &str) -> bool {
+        self.defs.borrow().functions.contains_key(name)
+            || matches!(self.variables.get(name), Some(Object::Function(_)))
+    }
+
+    fn call_named(
+        &mut self,
+        name: String,
+        evaled_args: Vec<Object>,
+    ) -> Result<Object, RuntimeError> {
+        //переменная с функцией важнее одноимённой функции
+        if let Some(Object::Function(c)) = self.variables.get(&name).cloned() {
+            return self.call_closure(&c, evaled_args);
+        }
+        self.call_function(name, evaled_args, None)
+    }
+
+    // "alias.f" живёт в модуле alias, "Point.area" в модуле, объявившем Point
+    fn foreign(&self, name: &str) -> Option<(Rc<RefCell<Interpritator>>, String)> {
+        let defs = self.defs.borrow();
+        //ключ типа сам содержит точки: modules.geometry.Point.area
+        if let Some((owner, _)) = name.rsplit_once('.')
+            && let Some(module) = defs.type_owners.get(owner)
+        {
+            return Some((module.clone(), name.to_string()));
+        }
+        let (owner, member) = name.split_once('.')?;
+        defs.modules
+            .get(owner)
+            .map(|module| (module.clone(), member.to_string()))
+    }
+
+    fn has_function(&self, name: &str) -> bool {
+        self.defs.borrow().functions.contains_key(name)
+            || self
+                .foreign(name)
+                .is_some_and(|(module, name)| module.borrow().has_function(&name))
+    }
+
+    // замыкание выполняется среди объявлений файла, где оно создано
+    fn call_closure(&mut self, c: &closure, args: Vec<Object>) -> Result<Object, RuntimeError> {
+        let own = std::mem::replace(&mut self.defs, c.home.clone());
+        let result = self.invoke(&c.func, args, c.env.clone());
+        self.defs = own;
+        result
+    }
+
+    fn invoke(
+        &mut self,
+        c_fn: &function,
+        evaled_args: Vec<Object>,
+        env: HashMap<String, Object>,
+    ) -> Result<Object, RuntimeError> {
+        let f_args = c_fn.get_args();
+        let scope = c_fn.get_scope();
+
+        if evaled_args.len() != f_args.len() {
+            return Err(RuntimeError::new(
+                ErrorKind::WrongArguments,
+                format!(
+                    "function '{}' takes {} arguments, got {}",
+                    c_fn.get_name(),
+                    f_args.len(),
+                    evaled_args.len()
+                ),
+            ));
+        }
+
+        let mut variables = scope.get_variables();
+        variables.extend(env);
+        for (f_arg, value) in f_args.iter().zip(evaled_args) {
+            if !self.type_matches(&f_arg.arg_type, &value) {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeMismatch,
+                    format!(
+                        "Type mismatch in function argument '{}': expected {:?}, got {:?}",
+                        f_arg.name,
+                        f_arg.arg_type,
+                        value.get_type()
+                    ),
+                ));
+            }
+            variables.insert(f_arg.name.clone(), value);
+        }
+
+        self.budget.borrow_mut().enter()?;
+        let temp = std::mem::replace(&mut self.variables, variables);
+        let state = self.run_scope(scope.get_nodes());
+        // переменные восстанавливаем и при ошибке, её могут поймать снаружи
+        self.variables = temp;
+        self.budget.borrow_mut().leave();
+
+        let return_value = match state? {
+            State::Return(value) => value,
+            State::Continue => Object::Void,
+        };
+        if !self.type_matches(&c_fn.get_return_value(), &return_value) {
+            return Err(RuntimeError::new(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "function '{}' must return {:?}, got {:?}",
+                    c_fn.get_name(),
+                    c_fn.get_return_value(),
+                    return_value.get_type()
+                ),
+            ));
         }
+        Ok(return_value)
     }
 }
@@ -0,0 +1,145 @@
+use std::time::{Duration, Instant};
+
+use crate::interpritator::error::{ErrorKind, RuntimeError};
+use crate::interpritator::objects::Object;
+
+// None - без ограничения
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub max_steps: Option<u64>,
+    pub max_depth: Option<usize>,
+    pub timeout: Option<Duration>,
+    pub max_memory: Option<usize>,
+}
+
+impl Limits {
+    // запуск студенческих решений на сервере проверки
+    pub fn grading() -> Self {
+        Self {
+            max_steps: Some(10_000_000),
+            max_depth: Some(1000),
+            timeout: Some(Duration::from_secs(10)),
+            max_memory: Some(64 * 1024 * 1024),
+        }
+    }
+}
+
+// счётчики общие для программы и всех её модулей
+#[derive(Debug, Default)]
+pub struct Budget {
+    limits: Limits,
+    steps: u64,
+    depth: usize,
+    allocated: usize,
+    started: Option<Instant>,
+}
+
+impl Budget {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    // часы опрашиваются не на каждом узле, это заметно дороже счётчика
+    pub fn step(&mut self) -> Result<(), RuntimeError> {
+        self.steps += 1;
+        if let Some(max) = self.limits.max_steps
+            && self.steps > max
+        {
+            return Err(exceeded(format!("step limit of {} exceeded", max)));
+        }
+        self.started.get_or_insert_with(Instant::now);
+        if self.steps.is_multiple_of(1024) {
+            return self.check_time();
+        }
+        Ok(())
+    }
+
+    pub fn check_time(&mut self) -> Result<(), RuntimeError> {
+        match (self.limits.timeout, self.remaining()) {
+            (Some(timeout), Some(left)) if left.is_zero() => Err(exceeded(format!(
+                "time limit of {} ms exceeded",
+                timeout.as_millis()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    // сколько осталось до дедлайна, None - время не ограничено
+    pub fn remaining(&mut self) -> Option<Duration> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        self.limits
+            .timeout
+            .map(|timeout| timeout.saturating_sub(started.elapsed()))
+    }
+
+    pub fn enter(&mut self) -> Result<(), RuntimeError> {
+        if let Some(max) = self.limits.max_depth
+            && self.depth >= max
+        {
+            return Err(exceeded(format!("call depth limit of {} exceeded", max)));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    pub fn allocate(&mut self, bytes: usize) -> Result<(), RuntimeError> {
+        self.allocated = self.allocated.saturating_add(bytes);
+        match self.limits.max_memory {
+            Some(max) if self.allocated > max => {
+                Err(exceeded(format!("memory limit of {} bytes exceeded", max)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// сколько байт занимает новая строка или коллекция, вложенные значения уже посчитаны
+pub fn footprint(value: &Object) -> usize {
+    match value {
+        Object::String(s) => s.len(),
+        Object::List(items) => items.borrow().len() * size_of::<Object>(),
+        Object::Map(entries) => entries.borrow().len() * 2 * size_of::<Object>(),
+        _ => 0,
+    }
+}
+
+// размер строки, которую построит оператор, None - считается по готовому значению
+pub fn binop_size(left: &Object, right: &Object, op: &str) -> Option<usize> {
+    match (left, right, op) {
+        (Object::String(l), Object::String(r), "+") => Some(l.len().saturating_add(r.len())),
+        (Object::String(l), Object::Int(n), "*") => {
+            Some(l.len().saturating_mul((*n).max(0) as usize))
+        }
+        _ => None,
+    }
+}
+
+// то же для встроенных функций, результат которых может быть намного больше аргументов
+pub fn builtin_size(name: &str, args: &[Object]) -> Option<usize> {
+    match (name, args) {
+        ("repeat", [Object::String(s), Object::Int(n)]) => {
+            Some(s.len().saturating_mul((*n).max(0) as usize))
+        }
+        ("replace", [Object::String(s), Object::String(from), Object::String(to)])
+            if !from.is_empty() =>
+        {
+            let count = s.matches(from.as_str()).count();
+            Some(s.len().saturating_add(count.saturating_mul(to.len())))
+        }
+        ("read_file" | "read_lines", [Object::String(path)]) => std::fs::metadata(path)
+            .ok()
+            .map(|meta| usize::try_from(meta.len()).unwrap_or(usize::MAX)),
+        _ => None,
+    }
+}
+
+fn exceeded(message: String) -> RuntimeError {
+    RuntimeError::new(ErrorKind::LimitExceeded, message)
+}
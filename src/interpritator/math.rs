@@ -0,0 +1,210 @@
+use crate::Object;
+use crate::interpritator::error::{ErrorKind, RuntimeError};
+use crate::interpritator::systemFunctions::wrong_args;
+
+pub const FUNCTIONS: [&str; 21] = [
+    "abs", "min", "max", "pow", "sqrt", "floor", "ceil", "round", "sin", "cos", "tan", "asin",
+    "acos", "atan", "atan2", "log", "exp", "clamp", "gcd", "hypot", "sign",
+];
+
+pub fn is_math_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+fn domain(name: &str, value: &dyn std::fmt::Display) -> RuntimeError {
+    RuntimeError::new(
+        ErrorKind::Domain,
+        format!("{} is not defined for {}", name, value),
+    )
+}
+
+fn overflow(name: &str, args: &[Object]) -> RuntimeError {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    RuntimeError::new(
+        ErrorKind::Overflow,
+        format!("{}({}) overflows", name, args.join(", ")),
+    )
+}
+
+// целые считаем в i128 и возвращаем в исходном типе
+fn integer(value: &Object) -> Option<i128> {
+    match value {
+        Object::Int(v) => Some(*v as i128),
+        Object::Long(v) => Some(*v),
+        Object::Short(v) => Some(*v as i128),
+        _ => None,
+    }
+}
+
+fn same_integer(like: &Object, value: i128) -> Option<Object> {
+    match like {
+        Object::Int(_) => i32::try_from(value).ok().map(Object::Int),
+        Object::Long(_) => Some(Object::Long(value)),
+        Object::Short(_) => i8::try_from(value).ok().map(Object::Short),
+        _ => None,
+    }
+}
+
+fn float(value: &Object) -> Option<f32> {
+    match value {
+        Object::Float(v) => Some(*v),
+        value => integer(value).map(|v| v as f32),
+    }
+}
+
+pub fn call(name: &str, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match name {
+        "abs" => match args.as_slice() {
+            [Object::Float(v)] => Ok(Object::Float(v.abs())),
+            [value] if integer(value).is_some() => integer(value)
+                .unwrap()
+                .checked_abs()
+                .and_then(|v| same_integer(value, v))
+                .ok_or_else(|| overflow(name, &args)),
+            _ => Err(wrong_args(name, "one number", &args)),
+        },
+        "sign" => match args.as_slice() {
+            [Object::Float(v)] if *v == 0.0 => Ok(Object::Float(0.0)),
+            [Object::Float(v)] => Ok(Object::Float(v.signum())),
+            [value] if integer(value).is_some() => {
+                Ok(same_integer(value, integer(value).unwrap().signum()).unwrap())
+            }
+            _ => Err(wrong_args(name, "one number", &args)),
+        },
+        "min" | "max" => match args.as_slice() {
+            [Object::Float(a), Object::Float(b)] => Ok(Object::Float(match name {
+                "min" => a.min(*b),
+                _ => a.max(*b),
+            })),
+            [a, b] if a.get_type() == b.get_type() && integer(a).is_some() => {
+                let (x, y) = (integer(a).unwrap(), integer(b).unwrap());
+                let picked = if name == "min" { x.min(y) } else { x.max(y) };
+                Ok(same_integer(a, picked).unwrap())
+            }
+            _ => Err(wrong_args(name, "two numbers of the same type", &args)),
+        },
+        "clamp" => match args.as_slice() {
+            [Object::Float(v), Object::Float(lo), Object::Float(hi)] => {
+                if lo.is_nan() || hi.is_nan() {
+                    return Err(domain(name, &"NaN bounds"));
+                }
+                if lo > hi {
+                    return Err(domain(name, &format!("bounds {} > {}", lo, hi)));
+                }
+                Ok(Object::Float(v.clamp(*lo, *hi)))
+            }
+            [v, lo, hi]
+                if integer(v).is_some()
+                    && v.get_type() == lo.get_type()
+                    && v.get_type() == hi.get_type() =>
+            {
+                let (x, lo, hi) = (
+                    integer(v).unwrap(),
+                    integer(lo).unwrap(),
+                    integer(hi).unwrap(),
+                );
+                if lo > hi {
+                    return Err(domain(name, &format!("bounds {} > {}", lo, hi)));
+                }
+                Ok(same_integer(v, x.clamp(lo, hi)).unwrap())
+            }
+            _ => Err(wrong_args(name, "three numbers of the same type", &args)),
+        },
+        "gcd" => match args.as_slice() {
+            [a, b] if a.get_type() == b.get_type() && integer(a).is_some() => {
+                let (Some(mut x), Some(mut y)) = (
+                    integer(a).unwrap().checked_abs(),
+                    integer(b).unwrap().checked_abs(),
+                ) else {
+                    return Err(overflow(name, &args));
+                };
+                while y != 0 {
+                    (x, y) = (y, x % y);
+                }
+                same_integer(a, x).ok_or_else(|| overflow(name, &args))
+            }
+            _ => Err(wrong_args(name, "two integers of the same type", &args)),
+        },
+        "pow" => match args.as_slice() {
+            [Object::Float(base), Object::Float(exp)] => {
+                let result = base.powf(*exp);
+                if result.is_nan() {
+                    return Err(domain(name, &format!("{} ** {}", base, exp)));
+                }
+                Ok(Object::Float(result))
+            }
+            [base, exp] if integer(base).is_some() && integer(exp).is_some() => {
+                let (b, e) = (integer(base).unwrap(), integer(exp).unwrap());
+                if e < 0 {
+                    return Err(domain(name, &format!("negative exponent {}", e)));
+                }
+                u32::try_from(e)
+                    .ok()
+                    .and_then(|e| b.checked_pow(e))
+                    .and_then(|v| same_integer(base, v))
+                    .ok_or_else(|| overflow(name, &args))
+            }
+            _ => Err(wrong_args(name, "two integers or two floats", &args)),
+        },
+        "floor" | "ceil" | "round" => match args.as_slice() {
+            [Object::Float(v)] => Ok(Object::Float(match name {
+                "floor" => v.floor(),
+                "ceil" => v.ceil(),
+                _ => v.round(),
+            })),
+            [value] if integer(value).is_some() => Ok(value.clone()),
+            _ => Err(wrong_args(name, "one number", &args)),
+        },
+        "atan2" | "hypot" => match args.as_slice() {
+            [a, b] if float(a).is_some() && float(b).is_some() => {
+                let (x, y) = (float(a).unwrap(), float(b).unwrap());
+                Ok(Object::Float(match name {
+                    "atan2" => x.atan2(y),
+                    _ => x.hypot(y),
+                }))
+            }
+            _ => Err(wrong_args(name, "two numbers", &args)),
+        },
+        "log" => match args.as_slice() {
+            [value] if float(value).is_some() => {
+                let v = float(value).unwrap();
+                if v <= 0.0 {
+                    return Err(domain(name, &v));
+                }
+                Ok(Object::Float(v.ln()))
+            }
+            [value, base] if float(value).is_some() && float(base).is_some() => {
+                let (v, b) = (float(value).unwrap(), float(base).unwrap());
+                if v <= 0.0 {
+                    return Err(domain(name, &v));
+                }
+                if b <= 0.0 || b == 1.0 {
+                    return Err(domain(name, &format!("base {}", b)));
+                }
+                Ok(Object::Float(v.log(b)))
+            }
+            _ => Err(wrong_args(name, "a number and an optional base", &args)),
+        },
+        _ => {
+            let [value] = args.as_slice() else {
+                return Err(wrong_args(name, "one number", &args));
+            };
+            let Some(v) = float(value) else {
+                return Err(wrong_args(name, "one number", &args));
+            };
+            let result = match name {
+                "sqrt" if v < 0.0 => return Err(domain(name, &v)),
+                "sqrt" => v.sqrt(),
+                "asin" | "acos" if !(-1.0..=1.0).contains(&v) => return Err(domain(name, &v)),
+                "asin" => v.asin(),
+                "acos" => v.acos(),
+                "sin" => v.sin(),
+                "cos" => v.cos(),
+                "tan" => v.tan(),
+                "atan" => v.atan(),
+                _ => v.exp(),
+            };
+            Ok(Object::Float(result))
+        }
+    }
+}
@@ -1,4 +1,12 @@
+pub mod dict;
+pub mod error;
+pub mod files;
 pub mod function;
 pub mod interpritator;
+pub mod limits;
+pub mod math;
 pub mod objects;
+pub mod permissions;
+pub mod random;
+pub mod strings;
 pub mod systemFunctions;
@@ -1,11 +1,15 @@
 use std::{
+    cell::RefCell,
     ffi::NulError,
     fmt::{self, write},
+    rc::Rc,
 };
 
+use crate::interpritator::dict::Dict;
+use crate::interpritator::function::closure;
 use crate::parser::parser::Type;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Object {
     Int(i32),
     Float(f32),
@@ -14,6 +18,12 @@ pub enum Object {
     Char(char),
     Long(i128),
     Short(i8),
+    List(Rc<RefCell<Vec<Object>>>),
+    Map(Rc<RefCell<Dict>>),
+    Struct(String, Rc<RefCell<Vec<(String, Object)>>>),
+    Enum(String, String, Vec<Object>),
+    Function(Rc<closure>),
+    None,
     Void,
 }
 
@@ -27,6 +37,26 @@ impl Object {
             Object::Char(_) => Type::Char,
             Object::Long(_) => Type::Long,
             Object::Short(_) => Type::Short,
+            Object::List(items) => {
+                let elem = items
+                    .borrow()
+                    .first()
+                    .map(|item| item.get_type())
+                    .unwrap_or(Type::Void);
+                Type::List(Box::new(elem))
+            }
+            Object::Map(entries) => {
+                let (key, value) = entries
+                    .borrow()
+                    .first()
+                    .map(|(k, v)| (k.get_type(), v.get_type()))
+                    .unwrap_or((Type::Void, Type::Void));
+                Type::Map(Box::new(key), Box::new(value))
+            }
+            Object::Struct(name, _) => Type::Struct(name.clone()),
+            Object::Enum(name, _, _) => Type::Enum(name.clone()),
+            Object::Function(c) => c.get_type(),
+            Object::None => Type::Optional(Box::new(Type::Void)),
             Object::Void => Type::Void,
         }
     }
@@ -73,6 +103,13 @@ impl Object {
             return None;
         }
     }
+    pub fn as_list(&self) -> Option<Rc<RefCell<Vec<Object>>>> {
+        if let Self::List(items) = self {
+            return Some(items.clone());
+        } else {
+            return None;
+        }
+    }
     pub fn as_bool(&self) -> Option<bool> {
         if let Self::Bool(value) = self {
             return Some(value.clone());
@@ -92,7 +129,58 @@ impl fmt::Display for Object {
             Object::Char(s) => write!(f, "{}", s),
             Object::Long(l) => write!(f, "{}", l),
             Object::Short(l) => write!(f, "{}", l),
+            Object::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Object::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+            Object::Struct(name, fields) => {
+                write!(f, "{} {{ ", short_name(name))?;
+                for (i, (field, value)) in fields.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", field, value)?;
+                }
+                write!(f, " }}")
+            }
+            Object::Enum(name, variant, values) => {
+                write!(f, "{}::{}", short_name(name), variant)?;
+                if !values.is_empty() {
+                    write!(f, "(")?;
+                    for (i, value) in values.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", value)?;
+                    }
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Object::Function(c) => write!(f, "<fn {}>", c.func.get_name()),
+            Object::None => write!(f, "none"),
             Object::Void => write!(f, "void"),
         }
     }
 }
+
+// типы из модулей называются modules.geometry.Point, печатается только Point
+fn short_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+use crate::interpritator::error::{ErrorKind, RuntimeError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capability {
+    FsRead,
+    FsWrite,
+    Process,
+    Network,
+    Env,
+    Quit,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::FsRead => "fs.read",
+            Capability::FsWrite => "fs.write",
+            Capability::Process => "process",
+            Capability::Network => "net",
+            Capability::Env => "env",
+            Capability::Quit => "quit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// какой capability нужен встроенной функции, None - функция безопасна
+pub fn required(name: &str) -> Option<Capability> {
+    match name {
+        "read_file" | "read_lines" | "file_exists" | "list_dir" => Some(Capability::FsRead),
+        "write_file" | "append_file" | "remove_file" | "make_dir" => Some(Capability::FsWrite),
+        "env" => Some(Capability::Env),
+        "quit" => Some(Capability::Quit),
+        _ => None,
+    }
+}
+
+// по умолчанию запрещено всё
+#[derive(Debug, Clone, Default)]
+pub struct Permissions {
+    read_roots: Vec<PathBuf>,
+    write_roots: Vec<PathBuf>,
+    process: bool,
+    network: bool,
+    env: bool,
+    quit: bool,
+}
+
+impl Permissions {
+    pub fn allow_read(&mut self, root: &Path) {
+        self.read_roots.push(resolve(root));
+    }
+
+    pub fn allow_write(&mut self, root: &Path) {
+        self.write_roots.push(resolve(root));
+    }
+
+    pub fn grant(&mut self, capability: Capability) {
+        match capability {
+            Capability::FsRead => self.allow_read(Path::new("/")),
+            Capability::FsWrite => self.allow_write(Path::new("/")),
+            Capability::Process => self.process = true,
+            Capability::Network => self.network = true,
+            Capability::Env => self.env = true,
+            Capability::Quit => self.quit = true,
+        }
+    }
+
+    // path нужен только для fs.read и fs.write
+    pub fn check(&self, capability: Capability, path: Option<&str>) -> Result<(), RuntimeError> {
+        let roots = match capability {
+            Capability::FsRead => &self.read_roots,
+            Capability::FsWrite => &self.write_roots,
+            Capability::Process if self.process => return Ok(()),
+            Capability::Network if self.network => return Ok(()),
+            Capability::Env if self.env => return Ok(()),
+            Capability::Quit if self.quit => return Ok(()),
+            _ => return Err(denied(capability)),
+        };
+        if roots.is_empty() {
+            return Err(denied(capability));
+        }
+        let Some(path) = path else {
+            return Ok(());
+        };
+        let target = resolve(Path::new(path));
+        if roots.iter().any(|root| target.starts_with(root)) {
+            Ok(())
+        } else {
+            Err(RuntimeError::new(
+                ErrorKind::PermissionDenied,
+                format!(
+                    "permission denied: {} for '{}' outside the allowed directories",
+                    capability, path
+                ),
+            ))
+        }
+    }
+}
+
+fn denied(capability: Capability) -> RuntimeError {
+    RuntimeError::new(
+        ErrorKind::PermissionDenied,
+        format!("permission denied: {}", capability),
+    )
+}
+
+// абсолютный путь без . и .., ссылки раскрываются у существующей части пути,
+// чтобы "data/../secret" или симлинк наружу не проходили проверку
+fn resolve(path: &Path) -> PathBuf {
+    let absolute = match std::env::current_dir() {
+        Ok(cwd) => cwd.join(path),
+        Err(_) => path.to_path_buf(),
+    };
+    let mut normal = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+
+    let mut existing = normal.as_path();
+    let mut rest: Vec<&std::ffi::OsStr> = Vec::new();
+    loop {
+        if let Ok(real) = existing.canonicalize() {
+            return rest.iter().rev().fold(real, |acc, part| acc.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return normal,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::Object;
+use crate::interpritator::error::{ErrorKind, RuntimeError};
+use crate::interpritator::systemFunctions::wrong_args;
+
+pub const FUNCTIONS: [&str; 5] = ["random_int", "random_float", "shuffle", "choice", "seed"];
+
+pub fn is_random_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+// splitmix64: одно число состояния, один и тот же seed даёт одну и ту же последовательность
+#[derive(Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(nanos)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn bits(&mut self) -> u128 {
+        ((self.next_u64() as u128) << 64) | self.next_u64() as u128
+    }
+
+    // равномерно в [0, n), без перекоса остатка от деления
+    fn below(&mut self, n: u128) -> u128 {
+        let zone = u128::MAX - u128::MAX % n;
+        loop {
+            let value = self.bits();
+            if value < zone {
+                return value % n;
+            }
+        }
+    }
+}
+
+fn integer(value: &Object) -> Option<i128> {
+    match value {
+        Object::Int(v) => Some(*v as i128),
+        Object::Long(v) => Some(*v),
+        Object::Short(v) => Some(*v as i128),
+        _ => None,
+    }
+}
+
+fn same_integer(like: &Object, value: i128) -> Object {
+    match like {
+        Object::Int(_) => Object::Int(value as i32),
+        Object::Short(_) => Object::Short(value as i8),
+        _ => Object::Long(value),
+    }
+}
+
+pub fn call(rng: &mut Rng, name: &str, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match (name, args.as_slice()) {
+        // обе границы включены
+        ("random_int", [lo, hi]) if lo.get_type() == hi.get_type() && integer(lo).is_some() => {
+            let (low, high) = (integer(lo).unwrap(), integer(hi).unwrap());
+            if low > high {
+                return Err(RuntimeError::new(
+                    ErrorKind::Domain,
+                    format!("random_int: empty range {}..{}", low, high),
+                ));
+            }
+            // весь диапазон long в u128 не помещается, тогда подходит любое число
+            let offset = match (high.wrapping_sub(low) as u128).checked_add(1) {
+                Some(span) => rng.below(span),
+                None => rng.bits(),
+            };
+            Ok(same_integer(lo, low.wrapping_add(offset as i128)))
+        }
+        ("random_float", []) => Ok(Object::Float(
+            (rng.next_u64() >> 40) as f32 / (1u64 << 24) as f32,
+        )),
+        ("shuffle", [Object::List(items)]) => {
+            let mut items = items.borrow_mut();
+            for i in (1..items.len()).rev() {
+                let j = rng.below(i as u128 + 1) as usize;
+                items.swap(i, j);
+            }
+            Ok(Object::Void)
+        }
+        ("choice", [Object::List(items)]) => {
+            let items = items.borrow();
+            if items.is_empty() {
+                return Err(RuntimeError::new(
+                    ErrorKind::EmptyCollection,
+                    "choice: list is empty".to_string(),
+                ));
+            }
+            let i = rng.below(items.len() as u128) as usize;
+            Ok(items[i].clone())
+        }
+        ("seed", [value]) if integer(value).is_some() => {
+            *rng = Rng::new(integer(value).unwrap() as u64);
+            Ok(Object::Void)
+        }
+        _ => Err(wrong_args(name, expected(name), &args)),
+    }
+}
+
+fn expected(name: &str) -> &'static str {
+    match name {
+        "random_int" => "two integers of the same type",
+        "random_float" => "no arguments",
+        "seed" => "one integer",
+        _ => "one list",
+    }
+}
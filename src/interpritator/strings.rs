@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::num::IntErrorKind;
+use std::rc::Rc;
+
+use crate::Object;
+use crate::interpritator::error::{ErrorKind, RuntimeError};
+use crate::interpritator::systemFunctions::wrong_args;
+
+pub const FUNCTIONS: [&str; 18] = [
+    "substr",
+    "index_of",
+    "contains",
+    "starts_with",
+    "ends_with",
+    "split",
+    "join",
+    "trim",
+    "to_upper",
+    "to_lower",
+    "replace",
+    "repeat",
+    "reverse",
+    "char_at",
+    "to_string",
+    "parse_int",
+    "parse_float",
+    "chars",
+];
+
+pub fn is_string_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+// индексы считаются в символах, а не в байтах utf-8
+fn char_index(name: &str, s: &str, index: i32, allow_end: bool) -> Result<usize, RuntimeError> {
+    let len = s.chars().count();
+    let limit = if allow_end {
+        len
+    } else {
+        len.saturating_sub(1)
+    };
+    if index < 0 || index as usize > limit || (!allow_end && len == 0) {
+        return Err(RuntimeError::new(
+            ErrorKind::IndexOutOfBounds,
+            format!(
+                "{}: index {} out of range for string of length {}",
+                name, index, len
+            ),
+        ));
+    }
+    Ok(index as usize)
+}
+
+fn strings(items: Vec<String>) -> Object {
+    Object::List(Rc::new(RefCell::new(
+        items.into_iter().map(Object::String).collect(),
+    )))
+}
+
+fn parse_error(name: &str, s: &str, what: &str) -> RuntimeError {
+    RuntimeError::new(
+        ErrorKind::Parse,
+        format!("{}: '{}' is not a valid {}", name, s, what),
+    )
+}
+
+pub fn call(name: &str, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match (name, args.as_slice()) {
+        ("substr", [Object::String(s), Object::Int(start)]) => {
+            let start = char_index(name, s, *start, true)?;
+            Ok(Object::String(s.chars().skip(start).collect()))
+        }
+        ("substr", [Object::String(s), Object::Int(start), Object::Int(count)]) => {
+            let from = char_index(name, s, *start, true)?;
+            let end = char_index(name, s, start.saturating_add(*count), true).map_err(|_| {
+                RuntimeError::new(
+                    ErrorKind::IndexOutOfBounds,
+                    format!(
+                        "substr: {} characters from {} out of range for string of length {}",
+                        count,
+                        start,
+                        s.chars().count()
+                    ),
+                )
+            })?;
+            if end < from {
+                return Err(RuntimeError::new(
+                    ErrorKind::IndexOutOfBounds,
+                    format!("substr: negative length {}", count),
+                ));
+            }
+            Ok(Object::String(
+                s.chars().skip(from).take(end - from).collect(),
+            ))
+        }
+        ("index_of", [Object::String(s), Object::String(sub)]) => Ok(match s.find(sub.as_str()) {
+            Some(byte) => Object::Int(s[..byte].chars().count() as i32),
+            None => Object::None,
+        }),
+        ("contains", [Object::String(s), Object::String(sub)]) => {
+            Ok(Object::Bool(s.contains(sub.as_str())))
+        }
+        ("contains", [Object::String(s), Object::Char(c)]) => Ok(Object::Bool(s.contains(*c))),
+        ("starts_with", [Object::String(s), Object::String(prefix)]) => {
+            Ok(Object::Bool(s.starts_with(prefix.as_str())))
+        }
+        ("ends_with", [Object::String(s), Object::String(suffix)]) => {
+            Ok(Object::Bool(s.ends_with(suffix.as_str())))
+        }
+        // пустой разделитель режет строку на символы, как их считают len и char_at
+        ("split", [Object::String(s), Object::String(sep)]) if sep.is_empty() => {
+            Ok(strings(s.chars().map(String::from).collect()))
+        }
+        ("split", [Object::String(s), Object::String(sep)]) => {
+            Ok(strings(s.split(sep.as_str()).map(String::from).collect()))
+        }
+        ("join", [Object::List(items), Object::String(sep)]) => {
+            let parts: Vec<String> = items.borrow().iter().map(|item| item.to_string()).collect();
+            Ok(Object::String(parts.join(sep)))
+        }
+        ("trim", [Object::String(s)]) => Ok(Object::String(s.trim().to_string())),
+        ("to_upper", [Object::String(s)]) => Ok(Object::String(s.to_uppercase())),
+        ("to_lower", [Object::String(s)]) => Ok(Object::String(s.to_lowercase())),
+        ("replace", [Object::String(_), Object::String(from), Object::String(_)])
+            if from.is_empty() =>
+        {
+            Err(RuntimeError::new(
+                ErrorKind::WrongArguments,
+                "replace: pattern must not be empty".to_string(),
+            ))
+        }
+        ("replace", [Object::String(s), Object::String(from), Object::String(to)]) => {
+            Ok(Object::String(s.replace(from.as_str(), to)))
+        }
+        ("repeat", [Object::String(s), Object::Int(n)]) => {
+            if *n < 0 {
+                return Err(RuntimeError::new(
+                    ErrorKind::WrongArguments,
+                    format!("repeat: count must not be negative, got {}", n),
+                ));
+            }
+            Ok(Object::String(s.repeat(*n as usize)))
+        }
+        ("reverse", [Object::String(s)]) => Ok(Object::String(s.chars().rev().collect())),
+        ("chars", [Object::String(s)]) => Ok(Object::List(Rc::new(RefCell::new(
+            s.chars().map(Object::Char).collect(),
+        )))),
+        ("char_at", [Object::String(s), Object::Int(i)]) => {
+            let i = char_index(name, s, *i, false)?;
+            Ok(Object::Char(s.chars().nth(i).unwrap()))
+        }
+        ("to_string", [value]) => Ok(Object::String(value.to_string())),
+        ("parse_int", [Object::String(s)]) => match s.trim().parse::<i32>() {
+            Ok(v) => Ok(Object::Int(v)),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+                ) =>
+            {
+                Err(RuntimeError::new(
+                    ErrorKind::Overflow,
+                    format!("parse_int: '{}' does not fit in int", s),
+                ))
+            }
+            Err(_) => Err(parse_error(name, s, "int")),
+        },
+        ("parse_float", [Object::String(s)]) => s
+            .trim()
+            .parse::<f32>()
+            .map(Object::Float)
+            .map_err(|_| parse_error(name, s, "float")),
+        _ => Err(wrong_args(name, expected(name), &args)),
+    }
+}
+
+fn expected(name: &str) -> &'static str {
+    match name {
+        "substr" => "(string, int) or (string, int, int)",
+        "index_of" | "starts_with" | "ends_with" | "split" => "(string, string)",
+        "contains" => "(string, string) or (string, char)",
+        "join" => "(list, string)",
+        "replace" => "(string, string, string)",
+        "repeat" | "char_at" => "(string, int)",
+        "to_string" => "one value",
+        _ => "one string",
+    }
+}
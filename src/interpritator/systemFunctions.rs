@@ -1,8 +1,12 @@
 use crate::Object;
 use crate::expressionNode;
+use crate::interpritator::error::{ErrorKind, RuntimeError};
+use crate::parser::parser::Type;
 
+use std::cell::RefCell;
 use std::io;
-use std::process::exit;
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub fn println(args: Vec<Object>) {
     for arg in args {
@@ -17,15 +21,272 @@ pub fn print(args: Vec<Object>) {
     }
 }
 
-pub fn scan() -> Object {
+pub fn scan() -> Result<Object, RuntimeError> {
     let mut input = String::new();
     io::stdin()
         .read_line(&mut input)
-        .expect("failed to readline");
+        .map_err(|e| RuntimeError::new(ErrorKind::Io, format!("failed to readline: {}", e)))?;
     let trimmed = input.trim_end().to_string();
-    Object::String(trimmed)
+    Ok(Object::String(trimmed))
 }
 
-pub fn quit() {
-    exit(0);
+pub fn wrong_args(name: &str, expected: &str, args: &[Object]) -> RuntimeError {
+    let got: Vec<String> = args.iter().map(|a| format!("{:?}", a.get_type())).collect();
+    RuntimeError::new(
+        ErrorKind::WrongArguments,
+        format!("{} expects {}, got ({})", name, expected, got.join(", ")),
+    )
+}
+
+// процесс завершает main.rs, чтобы вывод успел записаться
+pub fn quit(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [] => Err(RuntimeError::exit(0)),
+        [Object::Int(code)] => Err(RuntimeError::exit(*code)),
+        _ => Err(wrong_args("quit", "no arguments or one int", &args)),
+    }
+}
+
+pub fn env(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::String(name)] => Ok(std::env::var(name)
+            .map(Object::String)
+            .unwrap_or(Object::None)),
+        _ => Err(wrong_args("env", "one string", &args)),
+    }
+}
+
+pub fn len(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::List(items)] => Ok(Object::Int(items.borrow().len() as i32)),
+        [Object::Map(entries)] => Ok(Object::Int(entries.borrow().len() as i32)),
+        [Object::String(s)] => Ok(Object::Int(s.chars().count() as i32)),
+        _ => Err(wrong_args("len", "one list, map or string", &args)),
+    }
+}
+
+pub fn push(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::List(items), value] => {
+            if let Some(first) = items.borrow().first()
+                && !compatible(&first.get_type(), &value.get_type())
+            {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeMismatch,
+                    format!(
+                        "cannot push {:?} into list of {:?}",
+                        value.get_type(),
+                        first.get_type()
+                    ),
+                ));
+            }
+            items.borrow_mut().push(value.clone());
+            Ok(Object::Void)
+        }
+        _ => Err(wrong_args("push", "a list and a value", &args)),
+    }
+}
+
+// тип пустого списка или словаря неизвестен (Void), он подходит к любому,
+// none кладётся в любой список опциональных значений
+fn compatible(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Void, _) | (_, Type::Void) => true,
+        (Type::Optional(a), b) | (b, Type::Optional(a)) => compatible(a, b),
+        (Type::List(a), Type::List(b)) => compatible(a, b),
+        (Type::Map(ak, av), Type::Map(bk, bv)) => compatible(ak, bk) && compatible(av, bv),
+        (a, b) => a == b,
+    }
+}
+
+pub fn pop(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::List(items)] => items.borrow_mut().pop().ok_or_else(|| {
+            RuntimeError::new(
+                ErrorKind::EmptyCollection,
+                "pop from empty list".to_string(),
+            )
+        }),
+        _ => Err(wrong_args("pop", "one list", &args)),
+    }
+}
+
+pub fn has(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::Map(entries), key] => Ok(Object::Bool(entries.borrow().contains(key))),
+        _ => Err(wrong_args("has", "a map and a key", &args)),
+    }
+}
+
+pub fn remove(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::Map(entries), key] => entries.borrow_mut().remove(key).ok_or_else(|| {
+            RuntimeError::new(
+                ErrorKind::KeyNotFound,
+                format!("key {} not found in map", key),
+            )
+        }),
+        _ => Err(wrong_args("remove", "a map and a key", &args)),
+    }
+}
+
+pub fn keys(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::Map(entries)] => {
+            let keys = entries.borrow().keys();
+            Ok(Object::List(Rc::new(RefCell::new(keys))))
+        }
+        _ => Err(wrong_args("keys", "one map", &args)),
+    }
+}
+
+pub fn values(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.as_slice() {
+        [Object::Map(entries)] => {
+            let values = entries.borrow().values();
+            Ok(Object::List(Rc::new(RefCell::new(values))))
+        }
+        _ => Err(wrong_args("values", "one map", &args)),
+    }
+}
+
+pub const TIME_FUNCTIONS: [&str; 4] = ["now_ms", "clock", "sleep", "format_time"];
+
+pub fn is_time_function(name: &str) -> bool {
+    TIME_FUNCTIONS.contains(&name)
+}
+
+// в детерминированном режиме часы стоят на нуле и двигаются только через sleep
+#[derive(Debug)]
+pub struct Clock {
+    fake_ms: Option<i128>,
+    started: Instant,
+}
+
+impl Clock {
+    pub fn real() -> Self {
+        Self {
+            fake_ms: None,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn fake() -> Self {
+        Self {
+            fake_ms: Some(0),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::real()
+    }
+}
+
+pub fn now_ms(clock: &Clock, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if !args.is_empty() {
+        return Err(wrong_args("now_ms", "no arguments", &args));
+    }
+    let ms = match clock.fake_ms {
+        Some(ms) => ms,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i128)
+            .unwrap_or(0),
+    };
+    Ok(Object::Long(ms))
+}
+
+// секунды с начала программы, не зависят от перевода системных часов
+pub fn clock(clock: &Clock, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if !args.is_empty() {
+        return Err(wrong_args("clock", "no arguments", &args));
+    }
+    let seconds = match clock.fake_ms {
+        Some(ms) => ms as f32 / 1000.0,
+        None => clock.started.elapsed().as_secs_f32(),
+    };
+    Ok(Object::Float(seconds))
+}
+
+// настоящий сон не длиннее остатка до дедлайна, дальше лимит времени сработает сам
+pub fn sleep(
+    clock: &mut Clock,
+    remaining: Option<Duration>,
+    args: Vec<Object>,
+) -> Result<Object, RuntimeError> {
+    let ms = match args.as_slice() {
+        [Object::Int(ms)] => *ms as i128,
+        [Object::Long(ms)] => *ms,
+        _ => return Err(wrong_args("sleep", "one int or long", &args)),
+    };
+    if ms < 0 {
+        return Err(RuntimeError::new(
+            ErrorKind::Domain,
+            format!("sleep: negative duration {} ms", ms),
+        ));
+    }
+    match clock.fake_ms.as_mut() {
+        Some(now) => *now += ms,
+        None => {
+            let wanted = Duration::from_millis(u64::try_from(ms).unwrap_or(u64::MAX));
+            std::thread::sleep(remaining.map_or(wanted, |left| wanted.min(left)));
+        }
+    }
+    Ok(Object::Void)
+}
+
+// время в UTC: %Y %m %d %H %M %S, %f - миллисекунды, %% - сам знак
+pub fn format_time(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (ms, pattern) = match args.as_slice() {
+        [Object::Long(ms), Object::String(pattern)] => (*ms, pattern),
+        [Object::Int(ms), Object::String(pattern)] => (*ms as i128, pattern),
+        _ => return Err(wrong_args("format_time", "(long, string)", &args)),
+    };
+    let days = ms.div_euclid(86_400_000);
+    let of_day = ms.rem_euclid(86_400_000);
+    let (year, month, day) = civil_date(days);
+
+    let mut res = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => res.push_str(&format!("{:04}", year)),
+            Some('m') => res.push_str(&format!("{:02}", month)),
+            Some('d') => res.push_str(&format!("{:02}", day)),
+            Some('H') => res.push_str(&format!("{:02}", of_day / 3_600_000)),
+            Some('M') => res.push_str(&format!("{:02}", of_day / 60_000 % 60)),
+            Some('S') => res.push_str(&format!("{:02}", of_day / 1000 % 60)),
+            Some('f') => res.push_str(&format!("{:03}", of_day % 1000)),
+            Some('%') => res.push('%'),
+            other => {
+                let directive = other.map(|c| format!("%{}", c)).unwrap_or("%".to_string());
+                return Err(RuntimeError::new(
+                    ErrorKind::WrongArguments,
+                    format!("format_time: unknown directive '{}'", directive),
+                ));
+            }
+        }
+    }
+    Ok(Object::String(res))
+}
+
+// дни от 1970-01-01 в (год, месяц, день) по григорианскому календарю
+fn civil_date(days: i128) -> (i128, i128, i128) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
 }
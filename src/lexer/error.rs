@@ -0,0 +1,21 @@
+use std::fmt;
+
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct LexError {
+    message: String,
+    pos: Position,
+}
+
+impl LexError {
+    pub fn new(message: String, pos: Position) -> Self {
+        Self { message, pos }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "syntax error at {}: {}", self.pos, self.message)
+    }
+}
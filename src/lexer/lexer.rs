@@ -1,10 +1,14 @@
-use crate::lexer::token::Token;
+use crate::lexer::error::LexError;
+use crate::lexer::token::{Position, Token};
 use crate::lexer::token_type::*;
 
 pub struct Lexer {
     pos: usize,
     current: char,
     line_clone: Vec<char>,
+    line: usize,
+    column: usize,
+    start: Position,
 }
 
 impl Lexer {
@@ -13,26 +17,54 @@ impl Lexer {
             pos: 0,
             current: ' ',
             line_clone: Vec::new(),
+            line: 1,
+            column: 1,
+            start: Position { line: 1, column: 1 },
         }
     }
 
     fn advance(&mut self) {
+        if self.line_clone.get(self.pos) == Some(&'\n') {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
         self.pos += 1;
         if self.pos < self.line_clone.len() {
             self.current = self.line_clone[self.pos];
         }
     }
 
-    fn number_nize(&mut self) -> Token {
+    // 1_000, 0x1F, 0b1010, 0o17, 2.5, суффиксы 10L и 3s
+    fn number_nize(&mut self) -> Result<Token, LexError> {
+        let radix = match (self.current, self.line_clone.get(self.pos + 1)) {
+            ('0', Some('x')) => 16,
+            ('0', Some('b')) => 2,
+            ('0', Some('o')) => 8,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.advance();
+            self.advance();
+        }
+
         let mut res = String::new();
         let mut tok_type = TokenType::IntLiteral;
-        let mut dot_seen = false;
 
         while self.pos < self.line_clone.len() {
-            if self.current.is_ascii_digit() {
+            if self.current.is_digit(radix) {
                 res.push(self.current);
-            } else if self.current == '.' && !dot_seen {
-                dot_seen = true;
+            } else if self.current == '_' && !res.is_empty() {
+                // разделитель разрядов
+            } else if radix == 10
+                && self.current == '.'
+                && tok_type == TokenType::IntLiteral
+                && self
+                    .line_clone
+                    .get(self.pos + 1)
+                    .is_some_and(|c| c.is_ascii_digit())
+            {
                 tok_type = TokenType::FloatLiteral;
                 res.push(self.current);
             } else {
@@ -41,142 +73,542 @@ impl Lexer {
             self.advance();
         }
 
-        if tok_type == TokenType::FloatLiteral {
-            let parts: Vec<&str> = res.split('.').collect();
-            if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-                panic!("Invalid float literal: '{}'", res);
+        if res.is_empty() {
+            return Err(LexError::new(
+                "Expected digits after number prefix".to_string(),
+                self.start,
+            ));
+        }
+
+        if tok_type == TokenType::IntLiteral && self.pos < self.line_clone.len() {
+            let suffix = match self.current {
+                'L' | 'l' => Some(TokenType::LongLiteral),
+                's' | 'S' => Some(TokenType::ShortLiteral),
+                _ => None,
+            };
+            let after = self.line_clone.get(self.pos + 1).copied();
+            if let Some(suffix) = suffix
+                && !after.is_some_and(is_ident_continue)
+            {
+                tok_type = suffix;
+                self.advance();
             }
         }
 
-        Token::new(res, tok_type)
+        if self.pos < self.line_clone.len() && is_ident_continue(self.current) {
+            return Err(LexError::new(
+                format!(
+                    "Invalid digit or suffix '{}' in number literal",
+                    self.current
+                ),
+                self.here(),
+            ));
+        }
+
+        // целые отдаём парсеру в десятичном виде, тип выбирает он
+        if tok_type != TokenType::FloatLiteral {
+            let value = u128::from_str_radix(&res, radix).map_err(|_| {
+                LexError::new("Integer literal is too large".to_string(), self.start)
+            })?;
+            res = value.to_string();
+        }
+
+        Ok(Token::new(res, tok_type, self.start))
     }
 
     fn callinger_nize(&mut self) -> Token {
         let mut res = String::new();
 
-        while self.pos < self.line_clone.len() && self.current.is_alphabetic()
-            || self.current.is_ascii_digit()
-        {
+        while self.pos < self.line_clone.len() && is_ident_continue(self.current) {
             res.push(self.current);
             self.advance();
         }
 
         match res.as_str() {
-            "fn" => Token::new(res, TokenType::Fn),
-            "for" => Token::new(res, TokenType::For),
-            "while" => Token::new(res, TokenType::While),
-            "if" => Token::new(res, TokenType::If),
-            "true" => Token::new(res, TokenType::True),
-            "false" => Token::new(res, TokenType::False),
-            "string" => Token::new(res, TokenType::StringType),
-            "int" => Token::new(res, TokenType::IntType),
-            "bool" => Token::new(res, TokenType::BoolType),
-            "short" => Token::new(res, TokenType::ShortType),
-            "char" => Token::new(res, TokenType::CharType),
-            "float" => Token::new(res, TokenType::FloatType),
-            "long" => Token::new(res, TokenType::LongType),
-            "void" => Token::new(res, TokenType::VoidType),
-            "return" => Token::new(res, TokenType::Return),
-            _ => Token::new(res, TokenType::ID),
-        }
-    }
-
-    fn string_nize(&mut self) -> Token {
-        let quote_char = self.current;
-
-        if quote_char == '\'' {
-            self.advance();
+            "_" => Token::new(res, TokenType::Underscore, self.start),
+            "fn" => Token::new(res, TokenType::Fn, self.start),
+            "for" => Token::new(res, TokenType::For, self.start),
+            "in" => Token::new(res, TokenType::In, self.start),
+            "struct" => Token::new(res, TokenType::Struct, self.start),
+            "impl" => Token::new(res, TokenType::Impl, self.start),
+            "enum" => Token::new(res, TokenType::Enum, self.start),
+            "match" => Token::new(res, TokenType::Match, self.start),
+            "try" => Token::new(res, TokenType::Try, self.start),
+            "catch" => Token::new(res, TokenType::Catch, self.start),
+            "as" => Token::new(res, TokenType::As, self.start),
+            "import" => Token::new(res, TokenType::Import, self.start),
+            "pub" => Token::new(res, TokenType::Pub, self.start),
+            "while" => Token::new(res, TokenType::While, self.start),
+            "if" => Token::new(res, TokenType::If, self.start),
+            "else" => Token::new(res, TokenType::Else, self.start),
+            "let" => Token::new(res, TokenType::Let, self.start),
+            "mut" => Token::new(res, TokenType::Mut, self.start),
+            "const" => Token::new(res, TokenType::Const, self.start),
+            "true" => Token::new(res, TokenType::True, self.start),
+            "false" => Token::new(res, TokenType::False, self.start),
+            "none" => Token::new(res, TokenType::None, self.start),
+            "string" => Token::new(res, TokenType::StringType, self.start),
+            "int" => Token::new(res, TokenType::IntType, self.start),
+            "bool" => Token::new(res, TokenType::BoolType, self.start),
+            "short" => Token::new(res, TokenType::ShortType, self.start),
+            "char" => Token::new(res, TokenType::CharType, self.start),
+            "float" => Token::new(res, TokenType::FloatType, self.start),
+            "long" => Token::new(res, TokenType::LongType, self.start),
+            "void" => Token::new(res, TokenType::VoidType, self.start),
+            "return" => Token::new(res, TokenType::Return, self.start),
+            _ => Token::new(res, TokenType::ID, self.start),
+        }
+    }
 
-            if self.current == '\\' {
-                self.advance();
-                let esc = self.current;
-                self.advance();
+    fn here(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
 
-                if self.current != '\'' {
-                    panic!("Unterminated char literal");
-                }
-                self.advance();
+    // `++` и `--` - приращение только сразу после переменной, поля или элемента в конце строки,
+    // иначе это два оператора: 5--3 == 5 - -3
+    fn is_compound(&self, op: &str, tokens: &[Token]) -> bool {
+        if op != "++" && op != "--" {
+            return true;
+        }
+        let after_target = tokens
+            .last()
+            .is_some_and(|t| matches!(t.get_type(), TokenType::ID | TokenType::RSqBracket));
+        let rest = self.line_clone[self.pos + op.len()..]
+            .iter()
+            .take_while(|c| **c != '\n')
+            .find(|c| !c.is_whitespace());
+        after_target && matches!(rest, None | Some('}' | ';'))
+    }
 
-                let escaped_char = match esc {
-                    'n' => '\n',
-                    't' => '\t',
-                    'r' => '\r',
-                    '\\' => '\\',
-                    '\'' => '\'',
-                    _ => panic!("Unknown escape sequence"),
-                };
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.line_clone.get(self.pos + i) == Some(&c))
+    }
 
-                Token::new(escaped_char.to_string(), TokenType::CharLiteral)
-            } else {
-                let res = self.current;
-                self.advance();
+    fn escape(&mut self) -> Result<char, LexError> {
+        let pos = self.here();
+        self.advance();
+        if self.pos >= self.line_clone.len() {
+            return Err(LexError::new(
+                "Unterminated escape sequence".to_string(),
+                pos,
+            ));
+        }
 
-                if self.current != '\'' {
-                    panic!("Char literal ");
+        let esc = self.current;
+        self.advance();
+        match esc {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '0' => Ok('\0'),
+            '\\' => Ok('\\'),
+            '"' => Ok('"'),
+            '\'' => Ok('\''),
+            'u' => {
+                // \u{1F600}
+                if self.pos >= self.line_clone.len() || self.current != '{' {
+                    return Err(LexError::new("Expected '{' after \\u".to_string(), pos));
                 }
+                self.advance();
 
+                let mut hex = String::new();
+                while self.pos < self.line_clone.len() && self.current != '}' && hex.len() < 6 {
+                    hex.push(self.current);
+                    self.advance();
+                }
+                if self.pos >= self.line_clone.len() || self.current != '}' {
+                    return Err(LexError::new(
+                        "Unterminated unicode escape".to_string(),
+                        pos,
+                    ));
+                }
                 self.advance();
-                Token::new(res.to_string(), TokenType::CharLiteral)
+
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        LexError::new(format!("Invalid unicode escape '\\u{{{}}}'", hex), pos)
+                    })
             }
+            c => Err(LexError::new(
+                format!("Unknown escape sequence '\\{}'", c),
+                pos,
+            )),
+        }
+    }
+
+    fn char_nize(&mut self) -> Result<Token, LexError> {
+        self.advance();
+        if self.pos >= self.line_clone.len() || self.current == '\'' {
+            return Err(LexError::new("Empty char literal".to_string(), self.start));
+        }
+
+        let res = if self.current == '\\' {
+            self.escape()?
         } else {
+            let c = self.current;
             self.advance();
+            c
+        };
+
+        if self.pos >= self.line_clone.len() || self.current != '\'' {
+            return Err(LexError::new(
+                "Unterminated char literal".to_string(),
+                self.start,
+            ));
+        }
+        self.advance();
+        Ok(Token::new(
+            res.to_string(),
+            TokenType::CharLiteral,
+            self.start,
+        ))
+    }
 
-            let mut res = String::new();
+    // строка с {выражениями} даёт InterpStart, токены выражения, InterpMid ... InterpEnd
+    fn string_nize(&mut self, tokens: &mut Vec<Token>) -> Result<(), LexError> {
+        if self.current == '\'' {
+            tokens.push(self.char_nize()?);
+            return Ok(());
+        }
+        if self.starts_with("\"\"\"") {
+            tokens.push(self.multiline_nize()?);
+            return Ok(());
+        }
+        let start = self.start;
+        self.advance();
 
-            while self.pos < self.line_clone.len() && self.current != quote_char {
+        let mut res = String::new();
+        let mut segment_start = start;
+        let mut interpolated = false;
+        while self.pos < self.line_clone.len() && self.current != '"' {
+            if self.starts_with("{{") || self.starts_with("}}") {
                 res.push(self.current);
                 self.advance();
-            }
+                self.advance();
+            } else if self.current == '{' {
+                let tok_type = if interpolated {
+                    TokenType::InterpMid
+                } else {
+                    TokenType::InterpStart
+                };
+                tokens.push(Token::new(res.clone(), tok_type, segment_start));
+                res.clear();
+                interpolated = true;
 
-            if self.current != quote_char {
-                panic!("Unterminated string literal");
+                let brace = self.here();
+                self.advance();
+                let before = tokens.len();
+                self.lex_tokens(tokens, true)?;
+                if tokens.len() == before {
+                    return Err(LexError::new("Empty interpolation".to_string(), brace));
+                }
+                segment_start = self.here();
+            } else if self.current == '\\' {
+                res.push(self.escape()?);
+            } else {
+                res.push(self.current);
+                self.advance();
             }
+        }
+
+        if self.pos >= self.line_clone.len() {
+            return Err(LexError::new(
+                "Unterminated string literal".to_string(),
+                start,
+            ));
+        }
+        self.advance();
+
+        let tok_type = if interpolated {
+            TokenType::InterpEnd
+        } else {
+            TokenType::StringLiteral
+        };
+        tokens.push(Token::new(res, tok_type, segment_start));
+        Ok(())
+    }
+
+    // r"..." без обработки escape-последовательностей
+    fn raw_string_nize(&mut self) -> Result<Token, LexError> {
+        self.advance();
+        self.advance();
+
+        let mut res = String::new();
+        while self.pos < self.line_clone.len() && self.current != '"' {
+            res.push(self.current);
+            self.advance();
+        }
+
+        if self.pos >= self.line_clone.len() {
+            return Err(LexError::new(
+                "Unterminated raw string literal".to_string(),
+                self.start,
+            ));
+        }
+        self.advance();
+
+        Ok(Token::new(res, TokenType::StringLiteral, self.start))
+    }
 
+    fn multiline_nize(&mut self) -> Result<Token, LexError> {
+        for _ in 0..3 {
             self.advance();
+        }
 
-            Token::new(res, TokenType::StringLiteral)
+        // символ и флаг "получен из escape", такие не считаются отступом
+        let mut chars: Vec<(char, bool)> = Vec::new();
+        while !self.starts_with("\"\"\"") {
+            if self.pos >= self.line_clone.len() {
+                return Err(LexError::new(
+                    "Unterminated multi-line string".to_string(),
+                    self.start,
+                ));
+            }
+            if self.current == '\\' {
+                chars.push((self.escape()?, true));
+            } else {
+                chars.push((self.current, false));
+                self.advance();
+            }
+        }
+        for _ in 0..3 {
+            self.advance();
         }
+
+        Ok(Token::new(
+            dedent(chars),
+            TokenType::StringLiteral,
+            self.start,
+        ))
     }
 
-    pub fn token_nize(&mut self, line: String) -> Vec<Token> {
+    pub fn token_nize(&mut self, line: String) -> Result<Vec<Token>, LexError> {
         self.line_clone = line.chars().collect();
         self.pos = 0;
+        self.line = 1;
+        self.column = 1;
         let mut tokens: Vec<Token> = Vec::new();
 
         if !self.line_clone.is_empty() {
             self.current = self.line_clone[0];
         } else {
-            return tokens;
+            return Ok(tokens);
         }
 
+        self.lex_tokens(&mut tokens, false)?;
+
+        //for tok in &mut tokens {
+        //  println!("{}", tok.get_name_of_token());
+        //}
+
+        Ok(tokens)
+    }
+
+    // in_interpolation: читаем выражение внутри "{...}" до закрывающей скобки
+    fn lex_tokens(
+        &mut self,
+        tokens: &mut Vec<Token>,
+        in_interpolation: bool,
+    ) -> Result<(), LexError> {
+        let mut depth = 0;
         while self.pos < self.line_clone.len() {
+            self.start = Position {
+                line: self.line,
+                column: self.column,
+            };
+            if let Some(op) = COMPOUND_OPS.iter().find(|op| self.starts_with(op))
+                && self.is_compound(op, tokens)
+            {
+                tokens.push(Token::new(
+                    op.to_string(),
+                    TokenType::CompoundAssignment,
+                    self.start,
+                ));
+                for _ in 0..op.len() {
+                    self.advance();
+                }
+                continue;
+            }
             match self.current {
-                '#' => {self.advance(); continue},
-                '>' => {self.advance(); continue},
-                '+' => tokens.push(Token::new("+".to_string(), TokenType::Operator)),
-                '-' =>  if self.line_clone[self.pos + 1] == '>' { tokens.push(Token::new("->".to_string(), TokenType::RetOp)); self.advance() } else { tokens.push(Token::new("-".to_string(), TokenType::Operator))},
-                '*' => tokens.push(Token::new("*".to_string(), TokenType::Operator)),
-                '/' => tokens.push(Token::new("/".to_string(), TokenType::Operator)),
-                '=' => tokens.push(Token::new("=".to_string(), TokenType::Assignment)),
-                '(' => tokens.push(Token::new("(".to_string(), TokenType::LParen)),
-                ')' => tokens.push(Token::new(")".to_string(), TokenType::RParen)),
-                '{' => tokens.push(Token::new("{".to_string(), TokenType::LBracket)),
-                '}' => tokens.push(Token::new("}".to_string(), TokenType::RBracket)),
-                ';' => tokens.push(Token::new(";".to_string(), TokenType::SemiColon)),
-                ':' => tokens.push(Token::new(":".to_string(), TokenType::Colon)),
-                ',' => tokens.push(Token::new(",".to_string(), TokenType::Coma)),
+                '#' => {
+                    self.advance();
+                    continue;
+                }
+                '>' if self.starts_with(">>") => {
+                    tokens.push(Token::new(
+                        ">>".to_string(),
+                        TokenType::Operator,
+                        self.start,
+                    ));
+                    self.advance();
+                }
+                '<' if self.starts_with("<<") => {
+                    tokens.push(Token::new(
+                        "<<".to_string(),
+                        TokenType::Operator,
+                        self.start,
+                    ));
+                    self.advance();
+                }
+                '>' => {
+                    self.advance();
+                    continue;
+                }
+                '+' => tokens.push(Token::new("+".to_string(), TokenType::Operator, self.start)),
+                '-' => {
+                    if self.line_clone.get(self.pos + 1) == Some(&'>') {
+                        tokens.push(Token::new("->".to_string(), TokenType::RetOp, self.start));
+                        self.advance()
+                    } else {
+                        tokens.push(Token::new("-".to_string(), TokenType::Operator, self.start))
+                    }
+                }
+                '*' if self.starts_with("**") => {
+                    tokens.push(Token::new(
+                        "**".to_string(),
+                        TokenType::Operator,
+                        self.start,
+                    ));
+                    self.advance();
+                }
+                '*' => tokens.push(Token::new("*".to_string(), TokenType::Operator, self.start)),
+                '%' | '&' | '^' | '~' => tokens.push(Token::new(
+                    self.current.to_string(),
+                    TokenType::Operator,
+                    self.start,
+                )),
+                '/' => tokens.push(Token::new("/".to_string(), TokenType::Operator, self.start)),
+                '=' => {
+                    if self.line_clone.get(self.pos + 1) == Some(&'=') {
+                        tokens.push(Token::new(
+                            "==".to_string(),
+                            TokenType::Operator,
+                            self.start,
+                        ));
+                        self.advance();
+                    } else if self.line_clone.get(self.pos + 1) == Some(&'>') {
+                        tokens.push(Token::new(
+                            "=>".to_string(),
+                            TokenType::FatArrow,
+                            self.start,
+                        ));
+                        self.advance();
+                    } else {
+                        tokens.push(Token::new(
+                            "=".to_string(),
+                            TokenType::Assignment,
+                            self.start,
+                        ));
+                    }
+                }
+                '!' if self.line_clone.get(self.pos + 1) == Some(&'=') => {
+                    tokens.push(Token::new(
+                        "!=".to_string(),
+                        TokenType::Operator,
+                        self.start,
+                    ));
+                    self.advance();
+                }
+                '!' => tokens.push(Token::new("!".to_string(), TokenType::Operator, self.start)),
+                '(' => tokens.push(Token::new("(".to_string(), TokenType::LParen, self.start)),
+                ')' => tokens.push(Token::new(")".to_string(), TokenType::RParen, self.start)),
+                '{' => {
+                    depth += 1;
+                    tokens.push(Token::new("{".to_string(), TokenType::LBracket, self.start))
+                }
+                '}' => {
+                    if in_interpolation && depth == 0 {
+                        self.advance();
+                        return Ok(());
+                    }
+                    depth -= 1;
+                    tokens.push(Token::new("}".to_string(), TokenType::RBracket, self.start))
+                }
+                '[' => tokens.push(Token::new(
+                    "[".to_string(),
+                    TokenType::LSqBracket,
+                    self.start,
+                )),
+                ']' => tokens.push(Token::new(
+                    "]".to_string(),
+                    TokenType::RSqBracket,
+                    self.start,
+                )),
+                ';' => tokens.push(Token::new(
+                    ";".to_string(),
+                    TokenType::SemiColon,
+                    self.start,
+                )),
+                ':' => {
+                    if self.line_clone.get(self.pos + 1) == Some(&':') {
+                        tokens.push(Token::new(
+                            "::".to_string(),
+                            TokenType::DoubleColon,
+                            self.start,
+                        ));
+                        self.advance();
+                    } else {
+                        tokens.push(Token::new(":".to_string(), TokenType::Colon, self.start));
+                    }
+                }
+                ',' => tokens.push(Token::new(",".to_string(), TokenType::Coma, self.start)),
+                '.' => {
+                    if self.line_clone.get(self.pos + 1) == Some(&'.') {
+                        self.advance();
+                        if self.line_clone.get(self.pos + 1) == Some(&'=') {
+                            tokens.push(Token::new(
+                                "..=".to_string(),
+                                TokenType::DotDotEq,
+                                self.start,
+                            ));
+                            self.advance();
+                        } else {
+                            tokens.push(Token::new(
+                                "..".to_string(),
+                                TokenType::DotDot,
+                                self.start,
+                            ));
+                        }
+                    } else {
+                        tokens.push(Token::new(".".to_string(), TokenType::Dot, self.start));
+                    }
+                }
+                '|' => tokens.push(Token::new("|".to_string(), TokenType::Pipe, self.start)),
+                '?' => {
+                    if self.line_clone.get(self.pos + 1) == Some(&'?') {
+                        tokens.push(Token::new(
+                            "??".to_string(),
+                            TokenType::Operator,
+                            self.start,
+                        ));
+                        self.advance();
+                    } else {
+                        tokens.push(Token::new("?".to_string(), TokenType::Question, self.start));
+                    }
+                }
 
                 '"' | '\'' => {
-                    tokens.push(self.string_nize());
+                    self.string_nize(tokens)?;
+                    continue;
+                }
+                'r' if self.line_clone.get(self.pos + 1) == Some(&'"') => {
+                    tokens.push(self.raw_string_nize()?);
                     continue;
                 }
 
-                c if c.is_alphabetic() => {
+                c if is_ident_start(c) => {
                     tokens.push(self.callinger_nize());
                     continue;
                 }
                 c if c.is_ascii_digit() => {
-                    tokens.push(self.number_nize());
+                    tokens.push(self.number_nize()?);
                     continue;
                 }
                 ' ' | '\t' | '\n' => {
@@ -185,18 +617,81 @@ impl Lexer {
                 }
 
                 _ => {
-                    println!("error: unexpected xyeta '{}'", self.current);
-                    self.advance();
-                    continue;
+                    return Err(LexError::new(
+                        format!("unexpected character '{}'", self.current),
+                        self.start,
+                    ));
                 }
             }
             self.advance();
         }
 
-        //for tok in &mut tokens {
-        //  println!("{}", tok.get_name_of_token());
-        //}
+        if in_interpolation {
+            return Err(LexError::new(
+                "Unterminated interpolation, expected '}'".to_string(),
+                self.start,
+            ));
+        }
+        Ok(())
+    }
+}
+
+// длинные раньше коротких, чтобы `**=` не стал `*` и `*=`
+const COMPOUND_OPS: [&str; 13] = [
+    "**=", "<<=", ">>=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "++", "--",
+];
+
+// XID_Start / XID_Continue без таблиц unicode: буквы, цифры,
+// комбинируемые диакритические знаки и '_'
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c.is_alphanumeric()
+        || c == '_'
+        || matches!(
+            c,
+            '\u{0300}'..='\u{036F}'
+                | '\u{1AB0}'..='\u{1AFF}'
+                | '\u{1DC0}'..='\u{1DFF}'
+                | '\u{20D0}'..='\u{20FF}'
+                | '\u{FE20}'..='\u{FE2F}'
+                | '\u{200C}'
+                | '\u{200D}'
+        )
+}
+
+fn is_blank(line: &[(char, bool)]) -> bool {
+    line.iter()
+        .all(|&(c, escaped)| !escaped && c.is_whitespace())
+}
 
-        tokens
+// убираем перевод строки после открывающих кавычек, строку перед закрывающими и общий отступ
+fn dedent(chars: Vec<(char, bool)>) -> String {
+    let mut lines: Vec<&[(char, bool)]> =
+        chars.split(|&(c, escaped)| c == '\n' && !escaped).collect();
+    if lines.len() > 1 && is_blank(lines[0]) {
+        lines.remove(0);
+    }
+    if lines.len() > 1 && lines.last().is_some_and(|l| is_blank(l)) {
+        lines.pop();
     }
+
+    let indent = lines
+        .iter()
+        .filter(|l| !is_blank(l))
+        .map(|l| {
+            l.iter()
+                .take_while(|&&(c, escaped)| !escaped && (c == ' ' || c == '\t'))
+                .count()
+        })
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|l| l.iter().skip(indent).map(|&(c, _)| c).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
 }
@@ -1,3 +1,4 @@
+pub mod error;
 pub mod lexer;
 pub mod token;
 pub mod token_type;
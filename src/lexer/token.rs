@@ -1,19 +1,39 @@
+use std::fmt;
+
 use crate::lexer::token_type::TokenType;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Token {
     tok_type: TokenType,
     tok_value: String,
+    pos: Position,
 }
 
 impl Token {
-    pub fn new(value: String, token_type: TokenType) -> Self {
+    pub fn new(value: String, token_type: TokenType, pos: Position) -> Self {
         Self {
             tok_type: token_type,
             tok_value: value,
+            pos,
         }
     }
 
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+
     pub fn get_type(&self) -> TokenType {
         self.tok_type.clone()
     }
@@ -6,18 +6,37 @@ pub enum TokenType {
     RParen,
 
     IntLiteral,
+    LongLiteral,
+    ShortLiteral,
     StringLiteral,
     CharLiteral,
     FloatLiteral,
+    InterpStart,
+    InterpMid,
+    InterpEnd,
 
     //lang words
     Fn,
     If,
+    Else,
+    Let,
+    Mut,
+    Const,
     For,
+    In,
     While,
     Return,
     Break,
     Continue,
+    Struct,
+    Impl,
+    Enum,
+    Match,
+    Try,
+    Catch,
+    As,
+    Import,
+    Pub,
 
     ID,
 
@@ -32,14 +51,65 @@ pub enum TokenType {
 
     True,
     False,
+    None,
 
     LBracket,
     RBracket,
-
+    LSqBracket,
+    RSqBracket,
 
     RetOp,
     Assignment,
+    CompoundAssignment,
     Colon,
     SemiColon,
     Coma,
+    Pipe,
+    Question,
+    Dot,
+    DotDot,
+    DotDotEq,
+    DoubleColon,
+    FatArrow,
+    Underscore,
+}
+
+impl TokenType {
+    pub fn is_keyword(&self) -> bool {
+        matches!(
+            self,
+            TokenType::Fn
+                | TokenType::If
+                | TokenType::Else
+                | TokenType::Let
+                | TokenType::Mut
+                | TokenType::Const
+                | TokenType::For
+                | TokenType::In
+                | TokenType::While
+                | TokenType::Return
+                | TokenType::Break
+                | TokenType::Continue
+                | TokenType::Struct
+                | TokenType::Impl
+                | TokenType::Enum
+                | TokenType::Match
+                | TokenType::Try
+                | TokenType::Catch
+                | TokenType::As
+                | TokenType::Import
+                | TokenType::Pub
+                | TokenType::IntType
+                | TokenType::StringType
+                | TokenType::FloatType
+                | TokenType::BoolType
+                | TokenType::VoidType
+                | TokenType::CharType
+                | TokenType::ShortType
+                | TokenType::LongType
+                | TokenType::True
+                | TokenType::False
+                | TokenType::None
+        )
+    }
 }
@@ -3,37 +3,203 @@ mod lexer;
 mod parser;
 
 use std::fs::read_to_string;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
 
+use crate::interpritator::error::RuntimeError;
 use crate::interpritator::interpritator::Interpritator;
+use crate::interpritator::limits::Limits;
 use crate::interpritator::objects::Object;
+use crate::interpritator::permissions::{Capability, Permissions};
 use crate::lexer::lexer::Lexer;
 use crate::lexer::token;
 use crate::parser::nodes::*;
-use crate::parser::parser::Parser;
+use crate::parser::parser::{Node, Parser};
+
+// интерпретатор рекурсивный, на стандартном стеке глубокая рекурсия
+// в скрипте роняет процесс раньше, чем срабатывает --max-depth
+const STACK_SIZE: usize = 512 * 1024 * 1024;
 
 fn main() {
+    let runner = std::thread::Builder::new()
+        .name("main".to_string())
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .unwrap();
+    if runner.join().is_err() {
+        std::process::exit(101);
+    }
+}
+
+fn run() {
     let mut lex = Lexer::new();
     let mut par = Parser::new();
     let mut int = Interpritator::new();
 
     let mut code: String = String::new();
 
-    for line in read_lines("examples/hello_world.wn") {
+    // winter [run|grade] [--allow-...] [--max-...] [файл.wn] [аргументы скрипта...]
+    // grade запускает студенческий код: всё запрещено, пока не разрешено флагом,
+    // лимиты включены по умолчанию, а часы ненастоящие
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut permissions = Permissions::default();
+    let mut limits = Limits {
+        max_depth: Some(1000),
+        ..Limits::default()
+    };
+    let mut deterministic = false;
+    let mut seed: Option<u64> = None;
+    match args.first().map(String::as_str) {
+        Some("grade") => {
+            args.remove(0);
+            limits = Limits::grading();
+            deterministic = true;
+        }
+        Some("run") => {
+            args.remove(0);
+            permissions.grant(Capability::Quit);
+        }
+        _ => permissions.grant(Capability::Quit),
+    }
+
+    // всё после пути к файлу достаётся скрипту, даже флаги
+    let mut path: Option<String> = None;
+    let mut script_args: Vec<String> = Vec::new();
+    for arg in args {
+        if path.is_some() {
+            script_args.push(arg);
+        } else if arg == "--deterministic" {
+            deterministic = true;
+        } else if let Some(value) = arg.strip_prefix("--seed=") {
+            match value.parse::<u64>() {
+                Ok(value) => seed = Some(value),
+                Err(_) => {
+                    eprintln!("invalid seed '{}'", value);
+                    std::process::exit(2);
+                }
+            }
+        } else if arg.starts_with("--") {
+            if !allow(&mut permissions, &arg) && !limit(&mut limits, &arg) {
+                eprintln!("unknown option '{}'", arg);
+                std::process::exit(2);
+            }
+        } else {
+            path = Some(arg);
+        }
+    }
+    let path = path.unwrap_or_else(|| "examples/hello_world.wn".to_string());
+    int.set_permissions(permissions);
+    int.set_limits(limits);
+    int.set_args(script_args);
+    int.set_deterministic(deterministic);
+    if let Some(seed) = seed {
+        int.set_seed(seed);
+    }
+
+    par.set_file(std::path::Path::new(&path));
+    for line in read_lines(&path) {
         code = format!("{}{}\n", code, line);
     }
 
-    let tokens = lex.token_nize(code);
-    if !tokens.is_empty() {
-        let nodes = par.parse(tokens);
-        for node in nodes {
-            int.execute(node);
+    let tokens = match lex.token_nize(code) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
+    };
+    let nodes = if tokens.is_empty() {
+        Vec::new()
+    } else {
+        par.parse(tokens)
+    };
+    let code = match execute(&mut int, nodes) {
+        Ok(code) => code,
+        Err(e) => match e.exit_code() {
+            Some(code) => code,
+            None => {
+                eprintln!("{}", e);
+                1
+            }
+        },
+    };
+    let _ = std::io::stdout().flush();
+    std::process::exit(code);
+}
+
+fn execute(int: &mut Interpritator, nodes: Vec<Node>) -> Result<i32, RuntimeError> {
+    for node in nodes {
+        int.execute(node)?;
+    }
+    int.run_main()
+}
+
+// --allow-fs, --allow-fs=./data, --allow-fs-read=dir, --allow-env, ...
+fn allow(permissions: &mut Permissions, flag: &str) -> bool {
+    let (name, root) = match flag.split_once('=') {
+        Some((name, root)) => (name, Some(Path::new(root))),
+        None => (flag, None),
+    };
+    match (name, root) {
+        ("--allow-fs", Some(root)) => {
+            permissions.allow_read(root);
+            permissions.allow_write(root);
+        }
+        ("--allow-fs", None) => {
+            permissions.grant(Capability::FsRead);
+            permissions.grant(Capability::FsWrite);
+        }
+        ("--allow-fs-read", Some(root)) => permissions.allow_read(root),
+        ("--allow-fs-read", None) => permissions.grant(Capability::FsRead),
+        ("--allow-fs-write", Some(root)) => permissions.allow_write(root),
+        ("--allow-fs-write", None) => permissions.grant(Capability::FsWrite),
+        ("--allow-env", None) => permissions.grant(Capability::Env),
+        ("--allow-process", None) => permissions.grant(Capability::Process),
+        ("--allow-net", None) => permissions.grant(Capability::Network),
+        ("--allow-quit", None) => permissions.grant(Capability::Quit),
+        ("--allow-all", None) => {
+            for capability in [
+                Capability::FsRead,
+                Capability::FsWrite,
+                Capability::Process,
+                Capability::Network,
+                Capability::Env,
+                Capability::Quit,
+            ] {
+                permissions.grant(capability);
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+// --max-steps=N, --max-depth=N, --timeout=MS, --max-memory=BYTES, 0 снимает лимит
+fn limit(limits: &mut Limits, flag: &str) -> bool {
+    let Some((name, value)) = flag.split_once('=') else {
+        return false;
+    };
+    let Ok(value) = value.parse::<u64>() else {
+        return false;
+    };
+    let value = (value > 0).then_some(value);
+    match name {
+        "--max-steps" => limits.max_steps = value,
+        "--max-depth" => limits.max_depth = value.map(|v| v as usize),
+        "--timeout" => limits.timeout = value.map(Duration::from_millis),
+        "--max-memory" => limits.max_memory = value.map(|v| v as usize),
+        _ => return false,
     }
+    true
 }
 
 fn read_lines(filename: &str) -> Vec<String> {
     read_to_string(filename)
-        .unwrap()
+        .unwrap_or_else(|e| {
+            eprintln!("cannot read '{}': {}", filename, e);
+            std::process::exit(1);
+        })
         .lines()
         .map(|line| line.to_string())
         .collect()
@@ -1,2 +1,3 @@
+pub mod modules;
 pub mod nodes;
 pub mod parser;
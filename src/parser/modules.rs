@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use crate::parser::nodes::expressionNode;
+use crate::parser::parser::{Field, Node, Type, Variant};
+
+// разобранный файл и то, что он отдаёт наружу через `pub`
+#[derive(Debug)]
+pub struct Module {
+    pub path: String,
+    pub prefix: String,
+    pub nodes: Rc<Vec<Node>>,
+    pub functions: HashMap<String, Type>,
+    pub constants: HashMap<String, expressionNode>,
+    pub structs: HashMap<String, Vec<Field>>,
+    pub enums: HashMap<String, Vec<Variant>>,
+    pub methods: HashMap<String, Type>,
+    pub private: Vec<String>,
+}
+
+impl Module {
+    // alias.Point в импортирующем файле: ключ типа, если модуль его отдаёт
+    pub fn type_key(&self, name: &str) -> Option<String> {
+        let key = format!("{}.{}", self.prefix, name);
+        (self.structs.contains_key(&key) || self.enums.contains_key(&key)).then_some(key)
+    }
+}
+
+// один на программу: каждый файл лексится и парсится один раз
+#[derive(Debug, Default)]
+pub struct ModuleLoader {
+    cache: HashMap<PathBuf, Rc<Module>>,
+    loading: Vec<PathBuf>,
+    root: PathBuf,
+}
+
+impl ModuleLoader {
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = root;
+    }
+
+    // типы модуля называются по его пути от папки программы: modules/geometry.wn -> modules.geometry,
+    // так одноимённые типы разных модулей не сталкиваются
+    pub fn prefix(&self, path: &Path) -> String {
+        let relative = path
+            .strip_prefix(&self.root)
+            .unwrap_or(path)
+            .with_extension("");
+        relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    pub fn cached(&self, path: &PathBuf) -> Option<Rc<Module>> {
+        self.cache.get(path).cloned()
+    }
+
+    // файл, который сейчас грузится, не может импортировать сам себя по цепочке
+    pub fn begin(&mut self, path: PathBuf) -> Result<(), String> {
+        if let Some(start) = self.loading.iter().position(|p| *p == path) {
+            let chain: Vec<String> = self.loading[start..]
+                .iter()
+                .chain(std::iter::once(&path))
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err(format!("circular import: {}", chain.join(" -> ")));
+        }
+        self.loading.push(path);
+        Ok(())
+    }
+
+    pub fn finish(&mut self, path: PathBuf, module: Rc<Module>) {
+        self.loading.retain(|p| *p != path);
+        self.cache.insert(path, module);
+    }
+}
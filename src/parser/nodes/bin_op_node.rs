@@ -1,15 +1,22 @@
 use crate::expression_node::*;
+use crate::lexer::token::Position;
 
 #[derive(Debug, Clone)]
 pub struct binOpNode {
     left: expressionNode,
     right: expressionNode,
-    op: char,
+    op: String,
+    pos: Position,
 }
 
 impl binOpNode {
-    pub fn new(left: expressionNode, right: expressionNode, op: char) -> Self {
-        Self { left, right, op }
+    pub fn new(left: expressionNode, right: expressionNode, op: String, pos: Position) -> Self {
+        Self {
+            left,
+            right,
+            op,
+            pos,
+        }
     }
 
     pub fn get_left(&self) -> expressionNode {
@@ -20,7 +27,11 @@ impl binOpNode {
         return self.right.clone();
     }
 
-    pub fn get_op(&self) -> char {
+    pub fn get_op(&self) -> String {
         return self.op.clone();
     }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
 }
@@ -1,16 +1,19 @@
 use crate::expressionNode;
+use crate::lexer::token::Position;
 
 #[derive(Debug, Clone)]
 pub struct callNode {
     function_name: String,
     args: Vec<expressionNode>,
+    pos: Position,
 }
 
 impl callNode {
-    pub fn new(function_name: String, args: Vec<expressionNode>) -> Self {
+    pub fn new(function_name: String, args: Vec<expressionNode>, pos: Position) -> Self {
         Self {
             function_name,
             args,
+            pos,
         }
     }
 
@@ -29,4 +32,8 @@ impl callNode {
     pub fn get_arguments_len(&self) -> usize {
         self.args.len()
     }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
 }
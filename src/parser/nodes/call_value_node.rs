@@ -0,0 +1,27 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct callValueNode {
+    callee: expressionNode,
+    args: Vec<expressionNode>,
+    pos: Position,
+}
+
+impl callValueNode {
+    pub fn new(callee: expressionNode, args: Vec<expressionNode>, pos: Position) -> Self {
+        Self { callee, args, pos }
+    }
+
+    pub fn get_callee(&self) -> expressionNode {
+        self.callee.clone()
+    }
+
+    pub fn get_arguments(&self) -> Vec<expressionNode> {
+        self.args.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
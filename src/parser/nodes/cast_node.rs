@@ -0,0 +1,28 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+use crate::parser::parser::Type;
+
+#[derive(Debug, Clone)]
+pub struct castNode {
+    value: expressionNode,
+    target: Type,
+    pos: Position,
+}
+
+impl castNode {
+    pub fn new(value: expressionNode, target: Type, pos: Position) -> Self {
+        Self { value, target, pos }
+    }
+
+    pub fn get_value(&self) -> expressionNode {
+        self.value.clone()
+    }
+
+    pub fn get_target(&self) -> Type {
+        self.target.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
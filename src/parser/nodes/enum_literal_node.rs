@@ -0,0 +1,30 @@
+use crate::expressionNode;
+
+#[derive(Debug, Clone)]
+pub struct enumLiteralNode {
+    enum_name: String,
+    variant: String,
+    args: Vec<expressionNode>,
+}
+
+impl enumLiteralNode {
+    pub fn new(enum_name: String, variant: String, args: Vec<expressionNode>) -> Self {
+        Self {
+            enum_name,
+            variant,
+            args,
+        }
+    }
+
+    pub fn get_enum_name(&self) -> String {
+        self.enum_name.clone()
+    }
+
+    pub fn get_variant(&self) -> String {
+        self.variant.clone()
+    }
+
+    pub fn get_arguments(&self) -> Vec<expressionNode> {
+        self.args.clone()
+    }
+}
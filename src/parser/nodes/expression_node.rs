@@ -1,19 +1,47 @@
 use crate::bin_op_node::binOpNode;
 use crate::callNode;
+use crate::callValueNode;
+use crate::castNode;
+use crate::enumLiteralNode;
+use crate::fieldNode;
+use crate::functionNode;
+use crate::index_node::indexNode;
+use crate::matchNode;
+use crate::methodCallNode;
 use crate::number_node::numberNode;
 use crate::parser::parser::Type;
+use crate::slice_node::sliceNode;
+use crate::structLiteralNode;
+use crate::unaryNode;
 use crate::variable_node::variableNode;
 
 #[derive(Debug, Clone)]
 pub enum expressionNode {
     Number(numberNode),
     LongExpression(i128),
+    ShortExpression(i8),
     FloatExpression(f32),
     Variable(variableNode),
     BinOp(Box<binOpNode>),
     StringLiteral(String),
+    Interpolation(Vec<expressionNode>),
     DefaultValue(Type),
     FunctionCall(callNode),
     CharLiteral(char),
     Bool(bool),
+    None,
+    ListLiteral(Vec<expressionNode>),
+    MapLiteral(Vec<(expressionNode, expressionNode)>),
+    Index(Box<indexNode>),
+    Slice(Box<sliceNode>),
+    Cast(Box<castNode>),
+    Unary(Box<unaryNode>),
+    StructLiteral(structLiteralNode),
+    Field(Box<fieldNode>),
+    MethodCall(Box<methodCallNode>),
+    EnumLiteral(enumLiteralNode),
+    Match(Box<matchNode>),
+    Lambda(functionNode),
+    FunctionRef(String),
+    CallValue(Box<callValueNode>),
 }
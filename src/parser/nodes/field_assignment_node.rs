@@ -0,0 +1,42 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct fieldAssignmentNode {
+    target: expressionNode,
+    field: String,
+    value: expressionNode,
+    pos: Position,
+}
+
+impl fieldAssignmentNode {
+    pub fn new(
+        target: expressionNode,
+        field: String,
+        value: expressionNode,
+        pos: Position,
+    ) -> Self {
+        Self {
+            target,
+            field,
+            value,
+            pos,
+        }
+    }
+
+    pub fn get_target(&self) -> expressionNode {
+        self.target.clone()
+    }
+
+    pub fn get_field(&self) -> String {
+        self.field.clone()
+    }
+
+    pub fn get_value(&self) -> expressionNode {
+        self.value.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
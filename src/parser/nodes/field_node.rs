@@ -0,0 +1,27 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct fieldNode {
+    target: expressionNode,
+    field: String,
+    pos: Position,
+}
+
+impl fieldNode {
+    pub fn new(target: expressionNode, field: String, pos: Position) -> Self {
+        Self { target, field, pos }
+    }
+
+    pub fn get_target(&self) -> expressionNode {
+        self.target.clone()
+    }
+
+    pub fn get_field(&self) -> String {
+        self.field.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
@@ -0,0 +1,31 @@
+use crate::expressionNode;
+use crate::scopeNode;
+
+#[derive(Debug, Clone)]
+pub struct forNode {
+    var_name: String,
+    iterable: expressionNode,
+    body: scopeNode,
+}
+
+impl forNode {
+    pub fn new(var_name: String, iterable: expressionNode, body: scopeNode) -> Self {
+        Self {
+            var_name,
+            iterable,
+            body,
+        }
+    }
+
+    pub fn get_var_name(&self) -> String {
+        self.var_name.clone()
+    }
+
+    pub fn get_iterable(&self) -> expressionNode {
+        self.iterable.clone()
+    }
+
+    pub fn get_body(&self) -> scopeNode {
+        self.body.clone()
+    }
+}
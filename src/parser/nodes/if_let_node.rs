@@ -0,0 +1,42 @@
+use crate::expressionNode;
+use crate::scopeNode;
+
+#[derive(Debug, Clone)]
+pub struct ifLetNode {
+    name: String,
+    value: expressionNode,
+    body: scopeNode,
+    else_body: Option<scopeNode>,
+}
+
+impl ifLetNode {
+    pub fn new(
+        name: String,
+        value: expressionNode,
+        body: scopeNode,
+        else_body: Option<scopeNode>,
+    ) -> Self {
+        Self {
+            name,
+            value,
+            body,
+            else_body,
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_value(&self) -> expressionNode {
+        self.value.clone()
+    }
+
+    pub fn get_body(&self) -> scopeNode {
+        self.body.clone()
+    }
+
+    pub fn get_else_body(&self) -> Option<scopeNode> {
+        self.else_body.clone()
+    }
+}
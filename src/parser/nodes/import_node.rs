@@ -0,0 +1,38 @@
+use std::rc::Rc;
+
+use crate::parser::parser::Node;
+
+#[derive(Debug, Clone)]
+pub struct importNode {
+    alias: String,
+    path: String,
+    nodes: Rc<Vec<Node>>,
+    types: Vec<String>,
+}
+
+impl importNode {
+    pub fn new(alias: String, path: String, nodes: Rc<Vec<Node>>, types: Vec<String>) -> Self {
+        Self {
+            alias,
+            path,
+            nodes,
+            types,
+        }
+    }
+
+    pub fn get_alias(&self) -> String {
+        self.alias.clone()
+    }
+
+    pub fn get_path(&self) -> String {
+        self.path.clone()
+    }
+
+    pub fn get_nodes(&self) -> Rc<Vec<Node>> {
+        self.nodes.clone()
+    }
+
+    pub fn get_types(&self) -> Vec<String> {
+        self.types.clone()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct indexAssignmentNode {
+    target: expressionNode,
+    index: expressionNode,
+    value: expressionNode,
+    pos: Position,
+}
+
+impl indexAssignmentNode {
+    pub fn new(
+        target: expressionNode,
+        index: expressionNode,
+        value: expressionNode,
+        pos: Position,
+    ) -> Self {
+        Self {
+            target,
+            index,
+            value,
+            pos,
+        }
+    }
+
+    pub fn get_target(&self) -> expressionNode {
+        self.target.clone()
+    }
+
+    pub fn get_index(&self) -> expressionNode {
+        self.index.clone()
+    }
+
+    pub fn get_value(&self) -> expressionNode {
+        self.value.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
@@ -0,0 +1,27 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct indexNode {
+    target: expressionNode,
+    index: expressionNode,
+    pos: Position,
+}
+
+impl indexNode {
+    pub fn new(target: expressionNode, index: expressionNode, pos: Position) -> Self {
+        Self { target, index, pos }
+    }
+
+    pub fn get_target(&self) -> expressionNode {
+        self.target.clone()
+    }
+
+    pub fn get_index(&self) -> expressionNode {
+        self.index.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
@@ -0,0 +1,55 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+use crate::patternNode;
+use crate::scopeNode;
+
+#[derive(Debug, Clone)]
+pub enum armBody {
+    Expression(expressionNode),
+    Scope(scopeNode),
+}
+
+#[derive(Debug, Clone)]
+pub struct matchArm {
+    pattern: patternNode,
+    body: armBody,
+}
+
+impl matchArm {
+    pub fn new(pattern: patternNode, body: armBody) -> Self {
+        Self { pattern, body }
+    }
+
+    pub fn get_pattern(&self) -> patternNode {
+        self.pattern.clone()
+    }
+
+    pub fn get_body(&self) -> armBody {
+        self.body.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct matchNode {
+    subject: expressionNode,
+    arms: Vec<matchArm>,
+    pos: Position,
+}
+
+impl matchNode {
+    pub fn new(subject: expressionNode, arms: Vec<matchArm>, pos: Position) -> Self {
+        Self { subject, arms, pos }
+    }
+
+    pub fn get_subject(&self) -> expressionNode {
+        self.subject.clone()
+    }
+
+    pub fn get_arms(&self) -> Vec<matchArm> {
+        self.arms.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
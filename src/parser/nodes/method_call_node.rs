@@ -0,0 +1,42 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct methodCallNode {
+    target: expressionNode,
+    method: String,
+    args: Vec<expressionNode>,
+    pos: Position,
+}
+
+impl methodCallNode {
+    pub fn new(
+        target: expressionNode,
+        method: String,
+        args: Vec<expressionNode>,
+        pos: Position,
+    ) -> Self {
+        Self {
+            target,
+            method,
+            args,
+            pos,
+        }
+    }
+
+    pub fn get_target(&self) -> expressionNode {
+        self.target.clone()
+    }
+
+    pub fn get_method(&self) -> String {
+        self.method.clone()
+    }
+
+    pub fn get_arguments(&self) -> Vec<expressionNode> {
+        self.args.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
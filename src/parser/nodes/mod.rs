@@ -1,19 +1,55 @@
 pub mod assignment_node;
 pub mod bin_op_node;
 pub mod call_node;
+pub mod call_value_node;
+pub mod cast_node;
+pub mod enum_literal_node;
 pub mod expression_node;
+pub mod field_assignment_node;
+pub mod field_node;
+pub mod for_node;
 pub mod function_node;
+pub mod if_let_node;
+pub mod import_node;
+pub mod index_assignment_node;
+pub mod index_node;
+pub mod match_node;
+pub mod method_call_node;
 pub mod number_node;
+pub mod pattern_node;
 pub mod return_node;
 pub mod scope_node;
+pub mod slice_node;
+pub mod struct_literal_node;
+pub mod struct_node;
+pub mod try_node;
+pub mod unary_node;
 pub mod variable_node;
 
 pub use assignment_node::assignmentNode;
 pub use bin_op_node::binOpNode;
 pub use call_node::callNode;
+pub use call_value_node::callValueNode;
+pub use cast_node::castNode;
+pub use enum_literal_node::enumLiteralNode;
 pub use expression_node::expressionNode;
+pub use field_assignment_node::fieldAssignmentNode;
+pub use field_node::fieldNode;
+pub use for_node::forNode;
 pub use function_node::functionNode;
+pub use if_let_node::ifLetNode;
+pub use import_node::importNode;
+pub use index_assignment_node::indexAssignmentNode;
+pub use index_node::indexNode;
+pub use match_node::{armBody, matchArm, matchNode};
+pub use method_call_node::methodCallNode;
 pub use number_node::numberNode;
+pub use pattern_node::patternNode;
 pub use return_node::returnNode;
 pub use scope_node::scopeNode;
+pub use slice_node::sliceNode;
+pub use struct_literal_node::structLiteralNode;
+pub use struct_node::structNode;
+pub use try_node::tryNode;
+pub use unary_node::unaryNode;
 pub use variable_node::variableNode;
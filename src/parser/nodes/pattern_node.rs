@@ -0,0 +1,17 @@
+use crate::expressionNode;
+
+#[derive(Debug, Clone)]
+pub enum patternNode {
+    Wildcard,
+    Binding(String),
+    Literal(expressionNode),
+    Range(expressionNode, expressionNode, bool),
+    Variant(String, String, Vec<patternNode>),
+}
+
+impl patternNode {
+    // совпадает с любым значением своего типа
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(self, patternNode::Wildcard | patternNode::Binding(_))
+    }
+}
@@ -0,0 +1,42 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct sliceNode {
+    target: expressionNode,
+    start: Option<expressionNode>,
+    end: Option<expressionNode>,
+    pos: Position,
+}
+
+impl sliceNode {
+    pub fn new(
+        target: expressionNode,
+        start: Option<expressionNode>,
+        end: Option<expressionNode>,
+        pos: Position,
+    ) -> Self {
+        Self {
+            target,
+            start,
+            end,
+            pos,
+        }
+    }
+
+    pub fn get_target(&self) -> expressionNode {
+        self.target.clone()
+    }
+
+    pub fn get_start(&self) -> Option<expressionNode> {
+        self.start.clone()
+    }
+
+    pub fn get_end(&self) -> Option<expressionNode> {
+        self.end.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
@@ -0,0 +1,21 @@
+use crate::expressionNode;
+
+#[derive(Debug, Clone)]
+pub struct structLiteralNode {
+    name: String,
+    fields: Vec<(String, expressionNode)>,
+}
+
+impl structLiteralNode {
+    pub fn new(name: String, fields: Vec<(String, expressionNode)>) -> Self {
+        Self { name, fields }
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_fields(&self) -> Vec<(String, expressionNode)> {
+        self.fields.clone()
+    }
+}
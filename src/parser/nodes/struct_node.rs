@@ -0,0 +1,21 @@
+use crate::parser::parser::Field;
+
+#[derive(Debug, Clone)]
+pub struct structNode {
+    name: String,
+    fields: Vec<Field>,
+}
+
+impl structNode {
+    pub fn new(name: String, fields: Vec<Field>) -> Self {
+        Self { name, fields }
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_fields(&self) -> Vec<Field> {
+        self.fields.clone()
+    }
+}
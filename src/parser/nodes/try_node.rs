@@ -0,0 +1,30 @@
+use crate::scopeNode;
+
+#[derive(Debug, Clone)]
+pub struct tryNode {
+    body: scopeNode,
+    error_name: String,
+    handler: scopeNode,
+}
+
+impl tryNode {
+    pub fn new(body: scopeNode, error_name: String, handler: scopeNode) -> Self {
+        Self {
+            body,
+            error_name,
+            handler,
+        }
+    }
+
+    pub fn get_body(&self) -> scopeNode {
+        self.body.clone()
+    }
+
+    pub fn get_error_name(&self) -> String {
+        self.error_name.clone()
+    }
+
+    pub fn get_handler(&self) -> scopeNode {
+        self.handler.clone()
+    }
+}
@@ -0,0 +1,27 @@
+use crate::expressionNode;
+use crate::lexer::token::Position;
+
+#[derive(Debug, Clone)]
+pub struct unaryNode {
+    op: String,
+    value: expressionNode,
+    pos: Position,
+}
+
+impl unaryNode {
+    pub fn new(op: String, value: expressionNode, pos: Position) -> Self {
+        Self { op, value, pos }
+    }
+
+    pub fn get_op(&self) -> String {
+        self.op.clone()
+    }
+
+    pub fn get_value(&self) -> expressionNode {
+        self.value.clone()
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.pos
+    }
+}
@@ -1,23 +1,55 @@
 use core::panic;
 use std::any::Any;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::thread::current;
 use std::thread::panicking;
 use std::vec;
 
+use crate::armBody;
 use crate::assignmentNode;
 use crate::binOpNode;
+use crate::callValueNode;
+use crate::castNode;
+use crate::enumLiteralNode;
+use crate::fieldAssignmentNode;
+use crate::fieldNode;
+use crate::forNode;
 use crate::functionNode;
+use crate::ifLetNode;
+use crate::importNode;
+use crate::indexAssignmentNode;
+use crate::indexNode;
+use crate::interpritator::error::error_struct_fields;
+use crate::interpritator::files;
+use crate::interpritator::math;
 use crate::interpritator::objects::Object;
+use crate::interpritator::random;
+use crate::interpritator::strings;
+use crate::interpritator::systemFunctions;
 use crate::lexer;
+use crate::matchArm;
+use crate::matchNode;
+use crate::methodCallNode;
 use crate::parser::nodes::callNode;
 use crate::parser::nodes::{expressionNode, numberNode};
+use crate::patternNode;
 use crate::returnNode;
 use crate::scopeNode;
+use crate::sliceNode;
+use crate::structLiteralNode;
+use crate::structNode;
+use crate::tryNode;
+use crate::unaryNode;
 use crate::variableNode;
 
-use crate::lexer::token::Token;
+use crate::interpritator::interpritator::Interpritator;
+use crate::lexer::lexer::Lexer;
+use crate::lexer::token::{Position, Token};
 use crate::lexer::token_type::TokenType;
+use crate::parser::modules::{Module, ModuleLoader};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
@@ -29,6 +61,12 @@ pub enum Type {
     Long,
     Short,
     Void,
+    List(Box<Type>),
+    Map(Box<Type>, Box<Type>),
+    Struct(String),
+    Enum(String),
+    Function(Vec<Type>, Box<Type>),
+    Optional(Box<Type>),
 }
 
 #[derive(Debug, Clone)]
@@ -37,18 +75,66 @@ pub struct Arg {
     pub arg_type: Type,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub field_type: Type,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Vec<Type>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Node {
     Assignment(assignmentNode),
     ExpressionNode(expressionNode),
     FunctionNode(functionNode),
     ReturnNode(returnNode),
+    IndexAssignment(indexAssignmentNode),
+    For(forNode),
+    StructNode(structNode),
+    ImplNode(Vec<functionNode>),
+    // варианты нужны только парсеру, выполнять нечего
+    Enum,
+    Match(matchNode),
+    FieldAssignment(fieldAssignmentNode),
+    Try(tryNode),
+    IfLet(ifLetNode),
+    Import(importNode),
 }
 
+// имя, константа, `let` и тип, которые перекрыл параметр или переменная цикла
+type Shadowed = (
+    String,
+    Option<(expressionNode, Position)>,
+    Option<Position>,
+    Option<Type>,
+);
+
 pub struct Parser {
     tokens_clone: Vec<Token>,
     pos: usize,
     variable_types: HashMap<String, Type>,
+    function_types: HashMap<String, Type>,
+    struct_types: HashMap<String, Vec<Field>>,
+    enum_types: HashMap<String, Vec<Variant>>,
+    current_impl: Option<String>,
+    return_types: Vec<Type>,
+    literal_type: Option<Type>,
+    negative_literal: bool,
+    constants: HashMap<String, (expressionNode, Position)>,
+    immutable: HashMap<String, Position>,
+    pattern_scope: Vec<Shadowed>,
+    local_scope: Vec<Shadowed>,
+    captured: Vec<HashSet<String>>,
+    file: PathBuf,
+    loader: Rc<RefCell<ModuleLoader>>,
+    modules: HashMap<String, Rc<Module>>,
+    public: HashSet<String>,
+    prefix: String,
 }
 
 impl Parser {
@@ -57,11 +143,34 @@ impl Parser {
             tokens_clone: Vec::new(),
             pos: 0,
             variable_types: HashMap::new(),
+            function_types: HashMap::new(),
+            struct_types: HashMap::from([("Error".to_string(), error_struct_fields())]),
+            enum_types: HashMap::new(),
+            current_impl: None,
+            return_types: Vec::new(),
+            literal_type: None,
+            negative_literal: false,
+            constants: Self::builtin_constants(),
+            immutable: HashMap::new(),
+            pattern_scope: Vec::new(),
+            local_scope: Vec::new(),
+            captured: Vec::new(),
+            file: PathBuf::from("main.wn"),
+            loader: Rc::new(RefCell::new(ModuleLoader::default())),
+            modules: HashMap::new(),
+            public: HashSet::new(),
+            prefix: String::new(),
         }
     }
 
     fn current(&self) -> &Token {
-        &self.tokens_clone[self.pos]
+        self.tokens_clone.get(self.pos).unwrap_or_else(|| {
+            let last = self.tokens_clone.last().map(|t| t.get_position());
+            match last {
+                Some(pos) => panic!("Unexpected end of input after {}", pos),
+                None => panic!("Unexpected end of input"),
+            }
+        })
     }
 
     fn eat(&mut self, want_type: TokenType) {
@@ -77,6 +186,29 @@ impl Parser {
         }
     }
 
+    // имя в объявлении, ключевое слово на его месте - понятная ошибка
+    fn ident(&mut self, what: &str) -> String {
+        let tok = self.current().clone();
+        if tok.get_type() == TokenType::ID {
+            self.eat(TokenType::ID);
+            return tok.get_value();
+        }
+        if tok.get_type().is_keyword() {
+            panic!(
+                "'{}' is a reserved word and cannot be used as {} name (at {})",
+                tok.get_value(),
+                what,
+                tok.get_position()
+            );
+        }
+        panic!(
+            "Expected {} name at {}, got {:?}",
+            what,
+            tok.get_position(),
+            tok.get_value()
+        );
+    }
+
     fn statement_list(&mut self) -> Vec<Node> {
         let mut nodes: Vec<Node> = Vec::new();
 
@@ -92,25 +224,36 @@ impl Parser {
 
     fn statement(&mut self) -> Node {
         let tok = self.current().clone();
+        if tok.get_type().is_keyword()
+            && matches!(
+                self.tokens_clone.get(self.pos + 1).map(|t| t.get_type()),
+                Some(TokenType::Colon) | Some(TokenType::Assignment)
+            )
+        {
+            self.ident("a variable");
+        }
         match tok.get_type() {
             TokenType::ID => {
                 if self.tokens_clone.get(self.pos + 1).map(|t| t.get_type())
                     == Some(TokenType::Colon)
                 {
                     self.declaration()
-                } else if self.tokens_clone.get(self.pos + 1).map(|t| t.get_type())
-                    == Some(TokenType::Assignment)
-                {
+                } else if matches!(
+                    self.tokens_clone.get(self.pos + 1).map(|t| t.get_type()),
+                    Some(TokenType::Assignment) | Some(TokenType::CompoundAssignment)
+                ) {
                     self.reassignment()
                 } else if self.tokens_clone.get(self.pos + 1).map(|t| t.get_type())
                     == Some(TokenType::LParen)
                 {
                     let name = self.current().get_value();
+                    let pos = self.current().get_position();
                     self.eat(TokenType::ID);
                     self.eat(TokenType::LParen);
+                    let callee = self.callee_type(&name);
                     let mut args: Vec<expressionNode> = Vec::new();
                     while self.current().get_type() != TokenType::RParen {
-                        args.push(self.expr());
+                        args.push(self.expr_as(Self::param_type(&callee, args.len())));
                         if self.current().get_type() == TokenType::Coma {
                             self.eat(TokenType::Coma);
                         } else {
@@ -118,29 +261,47 @@ impl Parser {
                         }
                     }
                     self.eat(TokenType::RParen);
+                    self.check_call_args(&name, callee, &args);
                     Node::ExpressionNode(
-                        (expressionNode::FunctionCall((callNode::new(name, args)))),
+                        (expressionNode::FunctionCall((callNode::new(name, args, pos)))),
                     )
+                } else if matches!(
+                    self.tokens_clone.get(self.pos + 1).map(|t| t.get_type()),
+                    Some(TokenType::LSqBracket) | Some(TokenType::Dot)
+                ) {
+                    self.target_statement()
                 } else {
                     panic!("Invalid statement starting with ID");
                 }
             }
             TokenType::Fn => self.custom_func(),
+            TokenType::For => self.for_statement(),
+            TokenType::Try => self.try_statement(),
+            TokenType::Const => self.const_declaration(),
+            TokenType::Import => self.import_statement(),
+            TokenType::Pub => self.pub_declaration(),
+            TokenType::Let => self.let_declaration(),
+            TokenType::If => self.if_let_statement(),
+            TokenType::Struct => self.struct_declaration(),
+            TokenType::Impl => self.impl_block(),
+            TokenType::Enum => self.enum_declaration(),
+            TokenType::Match => Node::Match(self.match_node(true)),
             TokenType::Return => {
                 self.eat(TokenType::Return);
-                let value = self.expr();
+                let value = self.expr_as(self.return_types.last().cloned());
+                if let Some(expected) = self.return_types.last() {
+                    self.check_unwrapped(expected, &value, "return");
+                }
                 Node::ReturnNode((returnNode::new(value)))
             }
-            _ => Node::ExpressionNode(self.expr()),
+            _ => Node::ExpressionNode(self.expr_as(None)),
         }
     }
 
     fn reassignment(&mut self) -> Node {
         let name = self.current().get_value();
+        self.check_mutable(&name, self.current().get_position());
         self.eat(TokenType::ID);
-        self.eat(TokenType::Assignment);
-
-        let expr = self.expr();
 
         let t = self
             .variable_types
@@ -148,264 +309,2253 @@ impl Parser {
             .unwrap_or_else(|| panic!("no type found for variable '{}'", name))
             .clone();
 
+        if self.current().get_type() == TokenType::CompoundAssignment {
+            let current = expressionNode::Variable(variableNode::new(name.clone(), t.clone()));
+            let expr = self.compound_value(current);
+            let var_node = variableNode::new(name, t);
+            return Node::Assignment(assignmentNode::new(var_node, expr));
+        }
+
+        self.eat(TokenType::Assignment);
+        let expr = self.expr_as(Some(t.clone()));
+        self.check_unwrapped(&t, &expr, &format!("variable '{}'", name));
+
         let var_node = variableNode::new(name, t);
         Node::Assignment(assignmentNode::new(var_node, expr))
     }
 
-    fn declaration(&mut self) -> Node {
-        let name = self.current().get_value();
-        self.eat(TokenType::ID);
-        self.eat(TokenType::Colon);
+    fn target_statement(&mut self) -> Node {
+        let start = self.current().clone();
+        let target = self.expr_as(None);
 
-        let t = match self.current().get_type() {
-            TokenType::IntType => Type::Int,
-            TokenType::StringType => Type::String,
-            TokenType::BoolType => Type::Bool,
-            TokenType::CharType => Type::Char,
-            TokenType::LongType => Type::Long,
-            TokenType::ShortType => Type::Short,
-            TokenType::FloatType => Type::Float,
-            _ => panic!("Unexpected type"),
-        };
+        if self.pos < self.tokens_clone.len()
+            && matches!(
+                self.current().get_type(),
+                TokenType::Assignment | TokenType::CompoundAssignment
+            )
+        {
+            let pos = self.current().get_position();
+            // поле или элемент неизменяемой переменной тоже менять нельзя
+            if start.get_type() == TokenType::ID {
+                self.check_mutable(&start.get_value(), pos);
+            }
+            let value = if self.current().get_type() == TokenType::CompoundAssignment {
+                self.compound_value(target.clone())
+            } else {
+                self.eat(TokenType::Assignment);
+                self.expr_as(self.expr_type(&target))
+            };
 
-        self.variable_types.insert(name.clone(), t.clone());
-        self.eat(self.current().get_type());
+            if let (Some(expected), Some(value_type)) =
+                (self.expr_type(&target), self.expr_type(&value))
+                && !Self::type_fits(&expected, &value_type)
+            {
+                panic!(
+                    "Type mismatch in assignment: expected {:?}, got {:?}",
+                    expected, value_type
+                );
+            }
 
-        if self.current().get_type() == TokenType::Assignment {
-            self.eat(TokenType::Assignment);
-            let expr = self.expr();
+            return match target {
+                expressionNode::Index(index) => Node::IndexAssignment(indexAssignmentNode::new(
+                    index.get_target(),
+                    index.get_index(),
+                    value,
+                    pos,
+                )),
+                expressionNode::Field(field) => Node::FieldAssignment(fieldAssignmentNode::new(
+                    field.get_target(),
+                    field.get_field(),
+                    value,
+                    pos,
+                )),
+                _ => panic!(
+                    "Only list elements, map entries and struct fields can be assigned, got {:?}",
+                    target
+                ),
+            };
+        }
 
-            let extype = match &expr {
-                expressionNode::Number(_) => t == Type::Int,
-                expressionNode::StringLiteral(_) => t == Type::String,
-                expressionNode::DefaultValue(_) => true,
-                expressionNode::Variable(var) => var.get_type() == t,
-                
-                expressionNode::BinOp(_) => {
-                    fn is_binop_type_correct(expr: &expressionNode, expected: &Type) -> bool {
-                        match expr {
-                            expressionNode::BinOp(op) => {
-                                is_binop_type_correct(&op.get_left(), expected)
-                                    && is_binop_type_correct(&op.get_right(), expected)
-                            }
-                            expressionNode::Number(_) => expected == &Type::Int,
-                            expressionNode::StringLiteral(_) => expected == &Type::String,
-                            expressionNode::Bool(_) => expected == &Type::Bool,
-                            expressionNode::CharLiteral(_) => expected == &Type::Char,
-                            expressionNode::FloatExpression(_) => expected == &Type::Float,
-                            expressionNode::LongExpression(_) => expected == &Type::Long,
-                            expressionNode::Variable(var) => &var.get_type() == expected,
-                            _ => false,
-                        }
-                    }
+        Node::ExpressionNode(target)
+    }
 
-                    if !is_binop_type_correct(&expr, &t) {
-                        panic!("Type mismatch in binop for variable '{}'", name);
-                    }
-                    true
-                }
+    // PI и E можно перекрыть своим объявлением, но не присвоить
+    fn builtin_constants() -> HashMap<String, (expressionNode, Position)> {
+        let builtin = Position { line: 0, column: 0 };
+        HashMap::from([
+            (
+                "PI".to_string(),
+                (
+                    expressionNode::FloatExpression(std::f32::consts::PI),
+                    builtin,
+                ),
+            ),
+            (
+                "E".to_string(),
+                (
+                    expressionNode::FloatExpression(std::f32::consts::E),
+                    builtin,
+                ),
+            ),
+        ])
+    }
 
-                _ => false,
-            };
+    fn check_mutable(&self, name: &str, pos: Position) {
+        if let Some((_, declared)) = self.constants.get(name)
+            && declared.line == 0
+        {
+            panic!("Cannot assign to built-in constant '{}' (at {})", name, pos);
+        }
+        if let Some((_, declared)) = self.constants.get(name) {
+            panic!(
+                "Cannot assign to constant '{}' (at {}), declared at {}",
+                name, pos, declared
+            );
+        }
+        if let Some(declared) = self.immutable.get(name) {
+            panic!(
+                "Cannot assign to immutable variable '{}' (at {}), declared at {}; use `let mut`",
+                name, pos, declared
+            );
+        }
+        //лямбда получает копию внешних переменных, запись в неё потерялась бы
+        if self
+            .captured
+            .last()
+            .is_some_and(|names| names.contains(name))
+        {
+            panic!(
+                "Cannot assign to captured variable '{}' (at {}); closures capture by value",
+                name, pos
+            );
+        }
+    }
 
-            //if !extype {
-            //panic!("Type not need {:?}, got {:?}", t, expr);
-            //}
+    // новое объявление с тем же именем перекрывает константу или `let`
+    fn shadow(&mut self, name: &str) {
+        self.constants.remove(name);
+        self.immutable.remove(name);
+    }
 
-            return Node::Assignment(assignmentNode::new(variableNode::new(name, t), expr));
+    // параметр или переменная цикла перекрывает внешнее имя только до конца тела,
+    // старые записи возвращает restore
+    fn shadow_scoped(&mut self, name: &str, t: Type) -> Shadowed {
+        if let Some(names) = self.captured.last_mut() {
+            names.remove(name);
         }
+        (
+            name.to_string(),
+            self.constants.remove(name),
+            self.immutable.remove(name),
+            self.variable_types.insert(name.to_string(), t),
+        )
+    }
 
-        Node::Assignment(assignmentNode::new(
-            variableNode::new(name, t.clone()),
-            expressionNode::DefaultValue(t),
-        ))
+    fn restore(&mut self, saved: Vec<Shadowed>) {
+        for (name, constant, immutable, var_type) in saved.into_iter().rev() {
+            match constant {
+                Some(constant) => self.constants.insert(name.clone(), constant),
+                None => self.constants.remove(&name),
+            };
+            match immutable {
+                Some(pos) => self.immutable.insert(name.clone(), pos),
+                None => self.immutable.remove(&name),
+            };
+            match var_type {
+                Some(t) => self.variable_types.insert(name, t),
+                None => self.variable_types.remove(&name),
+            };
+        }
     }
 
-    fn parse_scope(&mut self) -> scopeNode {
-        self.eat(TokenType::LBracket);
-        let mut nodes: Vec<Node> = Vec::new();
-        nodes = self.statement_list();
-        self.eat(TokenType::RBracket);
-        scopeNode::new(nodes)
+    // объявление внутри тела функции живёт до конца тела, снаружи перекрывает насовсем
+    fn declare(&mut self, name: &str, t: Type) {
+        if let Some(names) = self.captured.last_mut() {
+            names.remove(name);
+        }
+        if self.return_types.is_empty() {
+            self.shadow(name);
+            self.variable_types.insert(name.to_string(), t);
+        } else {
+            let saved = self.shadow_scoped(name, t);
+            self.local_scope.push(saved);
+        }
     }
 
-    fn custom_func(&mut self) -> Node {
-        self.eat(TokenType::Fn);
+    // тело функции или лямбды; объявленное в нём снимается после разбора
+    fn function_body<T>(&mut self, return_val: Type, parse: fn(&mut Self) -> T) -> T {
+        let outer = std::mem::take(&mut self.local_scope);
+        self.return_types.push(return_val);
+        let body = parse(self);
+        self.return_types.pop();
+        let locals = std::mem::replace(&mut self.local_scope, outer);
+        self.restore(locals);
+        body
+    }
 
-        //обявления имя функции
-        let name = if self.current().get_type() == TokenType::ID {
-            let val = self.current().get_value();
-            self.eat(TokenType::ID);
-            val
+    fn let_declaration(&mut self) -> Node {
+        self.eat(TokenType::Let);
+        let mutable = self.current().get_type() == TokenType::Mut;
+        if mutable {
+            self.eat(TokenType::Mut);
+        }
+
+        let name = self.current().get_value();
+        let pos = self.current().get_position();
+        let start = self.pos;
+        let node = self.declaration();
+        if !mutable {
+            if !self.tokens_clone[start..self.pos]
+                .iter()
+                .any(|t| t.get_type() == TokenType::Assignment)
+            {
+                panic!(
+                    "Immutable variable '{}' needs an initializer (at {})",
+                    name, pos
+                );
+            }
+            self.immutable.insert(name, pos);
+        }
+        node
+    }
+
+    fn import_statement(&mut self) -> Node {
+        let pos = self.current().get_position();
+        self.eat(TokenType::Import);
+        if !self.return_types.is_empty() {
+            panic!("`import` is only allowed at top level (at {})", pos);
+        }
+
+        // import "math_utils.wn" или import utils.strings
+        let (relative, default_alias) = if self.current().get_type() == TokenType::StringLiteral {
+            let file = self.current().get_value();
+            self.eat(TokenType::StringLiteral);
+            let alias = Path::new(&file)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| panic!("Invalid module path '{}' (at {})", file, pos));
+            (PathBuf::from(file), alias)
         } else {
-            panic!("Expected name fn got: {}", self.current().get_value());
+            let mut segments = vec![self.ident("a module")];
+            while self.pos < self.tokens_clone.len() && self.current().get_type() == TokenType::Dot
+            {
+                self.eat(TokenType::Dot);
+                segments.push(self.ident("a module"));
+            }
+            let alias = segments.last().unwrap().clone();
+            let mut path: PathBuf = segments.iter().collect();
+            path.set_extension("wn");
+            (path, alias)
         };
 
-        self.eat(TokenType::LParen);
+        let alias =
+            if self.pos < self.tokens_clone.len() && self.current().get_type() == TokenType::As {
+                self.eat(TokenType::As);
+                self.ident("a module")
+            } else {
+                default_alias
+            };
 
-        //парс аргументов
-        let mut args: Vec<Arg> = Vec::new();
-        while self.current().get_type() != TokenType::RParen {
-            if self.current().get_type() == TokenType::ID {
-                let arg_name = self.current().get_value();
-                self.eat(TokenType::ID);
-                self.eat(TokenType::Colon);
+        let dir = self.file.parent().unwrap_or(Path::new("."));
+        let module = self.load_module(&dir.join(&relative), pos);
 
-                let arg_type = match self.current().get_type() {
-                    TokenType::IntType => {
-                        self.eat(TokenType::IntType);
-                        Type::Int
-                    }
-                    TokenType::StringType => {
-                        self.eat(TokenType::StringType);
-                        Type::String
-                    }
-                    TokenType::BoolType => {
-                        self.eat(TokenType::BoolType);
-                        Type::Bool
-                    }
-                    TokenType::VoidType => {
-                        self.eat(TokenType::VoidType);
-                        Type::Void
-                    }
-                    _ => panic!("unknown arg type"),
-                };
+        //типы модуля видны как alias.Type, ключи уже уникальны
+        for (key, fields) in &module.structs {
+            self.struct_types.insert(key.clone(), fields.clone());
+        }
+        for (key, variants) in &module.enums {
+            self.enum_types.insert(key.clone(), variants.clone());
+        }
+        for (name, t) in module.methods.iter() {
+            self.function_types.insert(name.clone(), t.clone());
+        }
+        for (name, t) in module.functions.iter() {
+            self.function_types
+                .insert(format!("{}.{}", alias, name), t.clone());
+        }
 
-                args.push(Arg {
-                    name: arg_name,
-                    arg_type,
-                });
+        let types = module
+            .structs
+            .keys()
+            .chain(module.enums.keys())
+            .cloned()
+            .collect();
+        self.modules.insert(alias.clone(), module.clone());
+        Node::Import(importNode::new(
+            alias,
+            module.path.clone(),
+            module.nodes.clone(),
+            types,
+        ))
+    }
 
-                if self.current().get_type() == TokenType::Coma {
-                    self.eat(TokenType::Coma);
-                } else {
-                    break;
-                }
-            } else {
-                panic!("Expected argument identifier");
-            }
+    fn load_module(&mut self, path: &Path, pos: Position) -> Rc<Module> {
+        let canonical = std::fs::canonicalize(path)
+            .unwrap_or_else(|_| panic!("Module '{}' not found (at {})", path.display(), pos));
+        if let Some(module) = self.loader.borrow().cached(&canonical) {
+            return module;
+        }
+        if let Err(e) = self.loader.borrow_mut().begin(canonical.clone()) {
+            panic!("{} (at {})", e, pos);
         }
 
-        self.eat(TokenType::RParen);
-        for arg in &args {
-            //println!("arg : {}",arg.name);
-            self.variable_types
-                .insert(arg.name.clone(), arg.arg_type.clone());
+        let code = std::fs::read_to_string(&canonical)
+            .unwrap_or_else(|e| panic!("Cannot read module '{}': {}", path.display(), e));
+        let tokens = Lexer::new()
+            .token_nize(code)
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+
+        let mut parser = Parser::new();
+        parser.file = canonical.clone();
+        parser.prefix = self.loader.borrow().prefix(&canonical);
+        parser.loader = self.loader.clone();
+        let nodes = parser.parse(tokens);
+        let module = Rc::new(parser.exports(canonical.display().to_string(), nodes));
+
+        self.loader.borrow_mut().finish(canonical, module.clone());
+        module
+    }
+
+    fn exports(&self, path: String, nodes: Vec<Node>) -> Module {
+        let public = |name: &String| self.public.contains(name);
+        let owner_public = |name: &String| {
+            name.rsplit_once('.')
+                .is_some_and(|(owner, _)| self.public.contains(owner))
+        };
+        Module {
+            path,
+            prefix: self.prefix.clone(),
+            nodes: Rc::new(nodes),
+            functions: self
+                .function_types
+                .iter()
+                .filter(|(name, _)| public(name))
+                .map(|(name, t)| (name.clone(), t.clone()))
+                .collect(),
+            constants: self
+                .constants
+                .iter()
+                .filter(|(name, _)| public(name))
+                .map(|(name, (literal, _))| (name.clone(), literal.clone()))
+                .collect(),
+            structs: self
+                .struct_types
+                .iter()
+                .filter(|(name, _)| public(name))
+                .map(|(name, fields)| (name.clone(), fields.clone()))
+                .collect(),
+            enums: self
+                .enum_types
+                .iter()
+                .filter(|(name, _)| public(name))
+                .map(|(name, variants)| (name.clone(), variants.clone()))
+                .collect(),
+            methods: self
+                .function_types
+                .iter()
+                .filter(|(name, _)| owner_public(name))
+                .map(|(name, t)| (name.clone(), t.clone()))
+                .collect(),
+            private: self
+                .function_types
+                .keys()
+                .chain(
+                    self.constants
+                        .iter()
+                        .filter(|(_, (_, declared))| declared.line != 0)
+                        .map(|(name, _)| name),
+                )
+                .filter(|name| !public(name))
+                .cloned()
+                .collect(),
         }
+    }
 
-        self.eat(TokenType::RetOp);
+    // всё верхнего уровня закрыто, пока не помечено `pub`
+    fn pub_declaration(&mut self) -> Node {
+        let pos = self.current().get_position();
+        self.eat(TokenType::Pub);
+        if !self.return_types.is_empty() || self.current_impl.is_some() {
+            panic!(
+                "`pub` is only allowed on top-level declarations (at {})",
+                pos
+            );
+        }
+        if !matches!(
+            self.current().get_type(),
+            TokenType::Fn | TokenType::Const | TokenType::Struct | TokenType::Enum
+        ) {
+            panic!(
+                "Only functions, constants, structs and enums can be `pub` (at {})",
+                pos
+            );
+        }
 
-        //обработка возращаемого значения
-        let return_val = match self.current().get_type() {
-            TokenType::IntType => {
-                self.eat(TokenType::IntType);
-                Type::Int
-            }
-            TokenType::StringType => {
-                self.eat(TokenType::StringType);
-                Type::String
-            }
-            TokenType::BoolType => {
-                self.eat(TokenType::BoolType);
-                Type::Bool
-            }
-            TokenType::VoidType => {
-                self.eat(TokenType::VoidType);
-                Type::Void
-            }
-            _ => panic!("Unknown return type: {}", self.current().get_value()),
+        let name = self
+            .tokens_clone
+            .get(self.pos + 1)
+            .map(|t| t.get_value())
+            .unwrap_or_default();
+        let name = match self.current().get_type() {
+            TokenType::Struct | TokenType::Enum => self.qualify(&name),
+            _ => name,
         };
+        let node = self.statement();
+        self.public.insert(name);
+        node
+    }
 
-        self.eat(TokenType::LBracket);
+    // alias.name: константы подставляются, функции вызываются в своём модуле
+    fn module_member(&mut self, alias: String, module: Rc<Module>) -> expressionNode {
+        self.eat(TokenType::ID);
+        self.eat(TokenType::Dot);
+        let pos = self.current().get_position();
+        let member = self.ident("a member");
 
-        let body_nodes = self.statement_list();
+        if let Some(key) = module.type_key(&member) {
+            return match self.current().get_type() {
+                TokenType::LBracket if self.struct_types.contains_key(&key) => {
+                    self.struct_literal(key)
+                }
+                TokenType::DoubleColon if self.enum_types.contains_key(&key) => {
+                    self.enum_literal(key)
+                }
+                _ => panic!(
+                    "Type '{}.{}' is not a value (at {}); use a literal",
+                    alias, member, pos
+                ),
+            };
+        }
 
-        self.eat(TokenType::RBracket);
+        if let Some(literal) = module.constants.get(&member) {
+            return literal.clone();
+        }
+        if let Some(callee) = module.functions.get(&member).cloned() {
+            if self.pos >= self.tokens_clone.len() || self.current().get_type() != TokenType::LParen
+            {
+                panic!(
+                    "Module function '{}.{}' must be called (at {})",
+                    alias, member, pos
+                );
+            }
+            let name = format!("{}.{}", alias, member);
+            let args = self.call_args(Some(callee.clone()));
+            self.check_call_args(&name, Some(callee), &args);
+            return expressionNode::FunctionCall(callNode::new(name, args, pos));
+        }
+        if module.private.contains(&member) {
+            panic!(
+                "'{}' is private to module '{}' (at {}); mark it `pub` to use it here",
+                member, alias, pos
+            );
+        }
+        panic!("Module '{}' has no member '{}' (at {})", alias, member, pos);
+    }
 
-        let scope = scopeNode::new(body_nodes);
+    fn const_declaration(&mut self) -> Node {
+        self.eat(TokenType::Const);
+        let pos = self.current().get_position();
+        let name = self.ident("a constant");
+        if let Some((_, declared)) = self.constants.get(&name)
+            && declared.line != 0
+        {
+            panic!(
+                "Constant '{}' (at {}) is already declared at {}",
+                name, pos, declared
+            );
+        }
+        self.eat(TokenType::Colon);
 
-        let func = Node::FunctionNode(functionNode::new(name, args, scope, return_val));
+        let t = self.parse_type();
+        if !matches!(
+            t,
+            Type::Int
+                | Type::Long
+                | Type::Short
+                | Type::Float
+                | Type::Char
+                | Type::String
+                | Type::Bool
+        ) {
+            panic!(
+                "Constant '{}' must have a primitive type, got {:?}",
+                name, t
+            );
+        }
+        self.eat(TokenType::Assignment);
+        let expr = self.expr_as(Some(t.clone()));
 
-        func
-    }
+        if !Self::is_constant_expr(&expr) {
+            panic!(
+                "Initializer of constant '{}' must be a constant expression (at {})",
+                name, pos
+            );
+        }
+        if let Some(actual) = self.expr_type(&expr)
+            && actual != t
+        {
+            panic!(
+                "Type mismatch for constant '{}': expected {:?}, got {:?}",
+                name, t, actual
+            );
+        }
 
-    fn expr(&mut self) -> expressionNode {
-        let left = self.term();
-        self.term_tail(left)
+        let value = Interpritator::new()
+            .eval_const(expr)
+            .unwrap_or_else(|e| panic!("Cannot evaluate constant '{}': {}", name, e));
+        let literal = Self::const_literal(value);
+
+        self.declare(&name, t.clone());
+        self.constants.insert(name.clone(), (literal.clone(), pos));
+        Node::Assignment(assignmentNode::new(variableNode::new(name, t), literal))
     }
 
-    fn term_tail(&mut self, mut left: expressionNode) -> expressionNode {
-        while self.pos < self.tokens_clone.len() {
-            let tok = self.current().clone();
-            if tok.get_type() == TokenType::Operator
-                && (tok.get_value() == "+" || tok.get_value() == "-")
-            {
-                let op = tok.get_value().chars().next().unwrap();
-                self.eat(TokenType::Operator);
-                let right = self.term();
-                let binop = binOpNode::new(left, right, op);
-                left = expressionNode::BinOp(Box::new(binop));
-            } else {
-                break;
+    // ссылки на константы уже подставлены как литералы
+    fn is_constant_expr(expr: &expressionNode) -> bool {
+        match expr {
+            expressionNode::Number(_)
+            | expressionNode::LongExpression(_)
+            | expressionNode::ShortExpression(_)
+            | expressionNode::FloatExpression(_)
+            | expressionNode::CharLiteral(_)
+            | expressionNode::StringLiteral(_)
+            | expressionNode::Bool(_) => true,
+            expressionNode::Interpolation(parts) => parts.iter().all(Self::is_constant_expr),
+            expressionNode::Unary(unary) => Self::is_constant_expr(&unary.get_value()),
+            expressionNode::Cast(cast) => Self::is_constant_expr(&cast.get_value()),
+            expressionNode::BinOp(op) => {
+                Self::is_constant_expr(&op.get_left()) && Self::is_constant_expr(&op.get_right())
             }
+            _ => false,
         }
-        left
     }
 
-    fn term(&mut self) -> expressionNode {
-        let left = self.factor();
-        self.factor_tail(left)
+    fn const_literal(value: Object) -> expressionNode {
+        match value {
+            Object::Int(v) => expressionNode::Number(numberNode::new(v)),
+            Object::Long(v) => expressionNode::LongExpression(v),
+            Object::Short(v) => expressionNode::ShortExpression(v),
+            Object::Float(v) => expressionNode::FloatExpression(v),
+            Object::Char(v) => expressionNode::CharLiteral(v),
+            Object::String(v) => expressionNode::StringLiteral(v),
+            Object::Bool(v) => expressionNode::Bool(v),
+            other => panic!("Constant value {} is not a literal", other),
+        }
     }
 
-    fn factor_tail(&mut self, mut left: expressionNode) -> expressionNode {
-        while self.pos < self.tokens_clone.len() {
-            let tok = self.current().clone();
-            if tok.get_type() == TokenType::Operator
-                && (tok.get_value() == "*" || tok.get_value() == "/")
+    // `x += e` разворачивается в `x = x + e`, `i++` в `i = i + 1`
+    fn compound_value(&mut self, current: expressionNode) -> expressionNode {
+        let tok = self.current().clone();
+        self.eat(TokenType::CompoundAssignment);
+        let target_type = self.expr_type(&current);
+
+        let compound = tok.get_value();
+        let increment = compound == "++" || compound == "--";
+        let op = if increment {
+            compound[..1].to_string()
+        } else {
+            compound[..compound.len() - 1].to_string()
+        };
+
+        let right = if increment {
+            if let Some(t) = &target_type
+                && !Self::is_integer_type(t)
             {
-                let op = tok.get_value().chars().next().unwrap();
-                self.eat(TokenType::Operator);
-                let right = self.factor();
-                let binop = binOpNode::new(left, right, op);
-                left = expressionNode::BinOp(Box::new(binop));
-            } else {
-                break;
+                panic!(
+                    "'{}' needs an integer target, got {:?} (at {})",
+                    compound,
+                    t,
+                    tok.get_position()
+                );
+            }
+            let one = Token::new("1".to_string(), TokenType::IntLiteral, tok.get_position());
+            Self::int_literal(&one, target_type.clone().unwrap_or(Type::Int), false)
+        } else {
+            self.expr_as(self.operand_type(&current))
+        };
+
+        if let (Some(left), Some(right)) = (&target_type, self.expr_type(&right)) {
+            let repeat = *left == Type::String && op == "*" && right == Type::Int;
+            if !Self::type_fits(left, &right) && !repeat {
+                panic!(
+                    "Type mismatch in '{}': {:?} and {:?} (at {})",
+                    compound,
+                    left,
+                    right,
+                    tok.get_position()
+                );
             }
         }
-        left
+
+        self.bin_op(
+            current,
+            right,
+            &Token::new(op, TokenType::Operator, tok.get_position()),
+        )
     }
 
-    fn factor(&mut self) -> expressionNode {
-        let tok = self.current().clone();
-        match tok.get_type() {
-            TokenType::IntLiteral => {
-                let value_str = tok.get_value();
-                let value: i128 = value_str.parse().expect("not number");
+    fn struct_declaration(&mut self) -> Node {
+        self.eat(TokenType::Struct);
 
-                self.eat(TokenType::IntLiteral);
+        let name = self.ident("a struct");
+        if name == "Error" {
+            panic!("'Error' is a built-in struct and cannot be redeclared");
+        }
+        let name = self.qualify(&name);
+        self.eat(TokenType::LBracket);
 
-                if value > 3600 {
-                    expressionNode::LongExpression(value)
-                } else {
-                    expressionNode::Number(numberNode::new(value as i32))
-                }
-            }
-            TokenType::FloatLiteral => {
-                //not work
-                //let value = self.current().get_value();
-                //let float_value = value.parse::<f32>().unwrap();
-                expressionNode::FloatExpression((0.0))
+        // регистрируем заранее, чтобы поля могли ссылаться на саму структуру
+        self.struct_types.insert(name.clone(), Vec::new());
+
+        let mut fields: Vec<Field> = Vec::new();
+        while self.current().get_type() != TokenType::RBracket {
+            let field_name = self.ident("a field");
+            self.eat(TokenType::Colon);
+            let field_type = self.parse_type();
+
+            if fields.iter().any(|f| f.name == field_name) {
+                panic!("Duplicate field '{}' in struct '{}'", field_name, name);
             }
-            TokenType::StringLiteral => {
-                self.eat(TokenType::StringLiteral);
-                expressionNode::StringLiteral(tok.get_value())
+            fields.push(Field {
+                name: field_name,
+                field_type,
+            });
+
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
             }
+        }
+        self.eat(TokenType::RBracket);
+
+        self.struct_types.insert(name.clone(), fields.clone());
+        Node::StructNode(structNode::new(name, fields))
+    }
+
+    fn enum_declaration(&mut self) -> Node {
+        self.eat(TokenType::Enum);
+
+        let name = self.ident("an enum");
+        let name = self.qualify(&name);
+        self.eat(TokenType::LBracket);
+
+        self.enum_types.insert(name.clone(), Vec::new());
+
+        let mut variants: Vec<Variant> = Vec::new();
+        while self.current().get_type() != TokenType::RBracket {
+            let variant_name = self.ident("a variant");
+
+            let mut fields: Vec<Type> = Vec::new();
+            if self.current().get_type() == TokenType::LParen {
+                self.eat(TokenType::LParen);
+                while self.current().get_type() != TokenType::RParen {
+                    fields.push(self.parse_type());
+                    if self.current().get_type() == TokenType::Coma {
+                        self.eat(TokenType::Coma);
+                    } else {
+                        break;
+                    }
+                }
+                self.eat(TokenType::RParen);
+            }
+
+            if variants.iter().any(|v| v.name == variant_name) {
+                panic!("Duplicate variant '{}' in enum '{}'", variant_name, name);
+            }
+            variants.push(Variant {
+                name: variant_name,
+                fields,
+            });
+
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
+            }
+        }
+        self.eat(TokenType::RBracket);
+
+        self.enum_types.insert(name, variants);
+        Node::Enum
+    }
+
+    fn variant(&self, enum_name: &str, variant: &str) -> Variant {
+        self.enum_types
+            .get(enum_name)
+            .and_then(|variants| variants.iter().find(|v| v.name == variant))
+            .cloned()
+            .unwrap_or_else(|| panic!("Enum '{}' has no variant '{}'", enum_name, variant))
+    }
+
+    fn enum_literal(&mut self, enum_name: String) -> expressionNode {
+        self.eat(TokenType::DoubleColon);
+        let variant_name = self.current().get_value();
+        self.eat(TokenType::ID);
+
+        let variant = self.variant(&enum_name, &variant_name);
+        let args = if self.pos < self.tokens_clone.len()
+            && self.current().get_type() == TokenType::LParen
+        {
+            self.call_args(Some(Type::Function(
+                variant.fields.clone(),
+                Box::new(Type::Enum(enum_name.clone())),
+            )))
+        } else {
+            Vec::new()
+        };
+
+        if variant.fields.len() != args.len() {
+            panic!(
+                "Variant '{}::{}' takes {} values, got {}",
+                enum_name,
+                variant_name,
+                variant.fields.len(),
+                args.len()
+            );
+        }
+        for (field_type, arg) in variant.fields.iter().zip(&args) {
+            if let Some(arg_type) = self.expr_type(arg)
+                && !Self::type_fits(field_type, &arg_type)
+            {
+                panic!(
+                    "Type mismatch in '{}::{}': expected {:?}, got {:?}",
+                    enum_name, variant_name, field_type, arg_type
+                );
+            }
+        }
+
+        expressionNode::EnumLiteral(enumLiteralNode::new(enum_name, variant_name, args))
+    }
+
+    fn match_node(&mut self, is_statement: bool) -> matchNode {
+        let pos = self.current().get_position();
+        self.eat(TokenType::Match);
+
+        let subject = self.expr_as(None);
+        let subject_type = self.expr_type(&subject);
+        self.eat(TokenType::LBracket);
+
+        let mut arms: Vec<matchArm> = Vec::new();
+        while self.current().get_type() != TokenType::RBracket {
+            //имена из образца видны только в теле своей ветки
+            let outer_scope = std::mem::take(&mut self.pattern_scope);
+            let pattern = self.parse_pattern(subject_type.as_ref());
+            self.eat(TokenType::FatArrow);
+
+            let body = if self.current().get_type() == TokenType::LBracket {
+                if !is_statement {
+                    panic!("Block arms are only allowed when match is used as a statement");
+                }
+                armBody::Scope(self.parse_scope())
+            } else if is_statement {
+                armBody::Scope(scopeNode::new(vec![self.statement()]))
+            } else {
+                armBody::Expression(self.expr())
+            };
+            let saved = std::mem::replace(&mut self.pattern_scope, outer_scope);
+            self.restore(saved);
+            arms.push(matchArm::new(pattern, body));
+
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            }
+        }
+        self.eat(TokenType::RBracket);
+
+        self.check_exhaustive(subject_type.as_ref(), &arms);
+        matchNode::new(subject, arms, pos)
+    }
+
+    fn literal_pattern(&mut self, expected: Option<&Type>) -> patternNode {
+        let literal = self.with_literal_type(expected.cloned(), Self::pattern_literal);
+        let literal_type = self.expr_type(&literal);
+        if let (Some(expected), Some(actual)) = (expected, &literal_type)
+            && !Self::type_fits(expected, actual)
+        {
+            panic!(
+                "Pattern type mismatch: expected {:?}, got {:?}",
+                expected, actual
+            );
+        }
+
+        let inclusive = match self.current().get_type() {
+            TokenType::DotDot => false,
+            TokenType::DotDotEq => true,
+            _ => return patternNode::Literal(literal),
+        };
+        self.eat(self.current().get_type());
+        let end = self.with_literal_type(expected.cloned(), Self::pattern_literal);
+
+        if !matches!(
+            literal_type,
+            Some(Type::Int) | Some(Type::Long) | Some(Type::Short) | Some(Type::Char)
+        ) || self.expr_type(&end) != literal_type
+        {
+            panic!("Range patterns need two int or char bounds of the same type");
+        }
+        patternNode::Range(literal, end, inclusive)
+    }
+
+    // в образцах минус может стоять только перед целым литералом: -1 => ..., -10..0 => ...
+    fn pattern_literal(&mut self) -> expressionNode {
+        if self.current().get_type() == TokenType::Operator && self.current().get_value() == "-" {
+            self.eat(TokenType::Operator);
+            if !matches!(
+                self.current().get_type(),
+                TokenType::IntLiteral | TokenType::LongLiteral | TokenType::ShortLiteral
+            ) {
+                panic!(
+                    "Expected integer literal after '-' in pattern at {}",
+                    self.current().get_position()
+                );
+            }
+            self.negative_literal = true;
+        }
+        self.primary()
+    }
+
+    fn parse_pattern(&mut self, expected: Option<&Type>) -> patternNode {
+        let tok = self.current().clone();
+        match tok.get_type() {
+            TokenType::Underscore => {
+                self.eat(TokenType::Underscore);
+                patternNode::Wildcard
+            }
+            TokenType::Operator if tok.get_value() == "-" => self.literal_pattern(expected),
+            TokenType::IntLiteral
+            | TokenType::LongLiteral
+            | TokenType::ShortLiteral
+            | TokenType::CharLiteral
+            | TokenType::StringLiteral
+            | TokenType::True
+            | TokenType::False => self.literal_pattern(expected),
+            TokenType::ID
+                if self.type_ref().is_some_and(|(key, len)| {
+                    self.enum_types.contains_key(&key)
+                        && self.tokens_clone.get(self.pos + len).map(|t| t.get_type())
+                            == Some(TokenType::DoubleColon)
+                }) =>
+            {
+                let enum_name = self.eat_type_ref();
+                self.eat(TokenType::DoubleColon);
+                let variant_name = self.current().get_value();
+                self.eat(TokenType::ID);
+
+                if let Some(expected) = expected
+                    && expected != &Type::Enum(enum_name.clone())
+                {
+                    panic!(
+                        "Pattern type mismatch: expected {:?}, got {:?}",
+                        expected,
+                        Type::Enum(enum_name)
+                    );
+                }
+
+                let variant = self.variant(&enum_name, &variant_name);
+                let mut fields: Vec<patternNode> = Vec::new();
+                if self.current().get_type() == TokenType::LParen {
+                    self.eat(TokenType::LParen);
+                    while self.current().get_type() != TokenType::RParen {
+                        let field_type = variant.fields.get(fields.len()).cloned();
+                        fields.push(self.parse_pattern(field_type.as_ref()));
+                        if self.current().get_type() == TokenType::Coma {
+                            self.eat(TokenType::Coma);
+                        } else {
+                            break;
+                        }
+                    }
+                    self.eat(TokenType::RParen);
+                }
+
+                if fields.len() != variant.fields.len() {
+                    panic!(
+                        "Pattern '{}::{}' needs {} values, got {}",
+                        enum_name,
+                        variant_name,
+                        variant.fields.len(),
+                        fields.len()
+                    );
+                }
+                patternNode::Variant(enum_name, variant_name, fields)
+            }
+            TokenType::ID => {
+                let name = tok.get_value();
+                self.eat(TokenType::ID);
+
+                let Some(t) = expected else {
+                    panic!("Cannot infer type of pattern binding '{}'", name);
+                };
+                let saved = self.shadow_scoped(&name, t.clone());
+                self.pattern_scope.push(saved);
+                patternNode::Binding(name)
+            }
+            _ => panic!("Unexpected token in pattern: {:?}", tok),
+        }
+    }
+
+    fn check_exhaustive(&self, subject_type: Option<&Type>, arms: &[matchArm]) {
+        if arms.iter().any(|arm| arm.get_pattern().is_irrefutable()) {
+            return;
+        }
+
+        match subject_type {
+            Some(Type::Enum(name)) => {
+                let missing: Vec<String> = self.enum_types[name]
+                    .iter()
+                    .filter(|variant| {
+                        !arms.iter().any(|arm| match arm.get_pattern() {
+                            patternNode::Variant(_, v, fields) => {
+                                v == variant.name && fields.iter().all(|f| f.is_irrefutable())
+                            }
+                            _ => false,
+                        })
+                    })
+                    .map(|variant| format!("{}::{}", name, variant.name))
+                    .collect();
+
+                if !missing.is_empty() {
+                    panic!("Non-exhaustive match: {} not covered", missing.join(", "));
+                }
+            }
+            Some(Type::Bool) => {
+                let covers = |value: bool| {
+                    arms.iter().any(|arm| {
+                        matches!(arm.get_pattern(),
+                            patternNode::Literal(expressionNode::Bool(b)) if b == value)
+                    })
+                };
+                if !covers(true) || !covers(false) {
+                    panic!("Non-exhaustive match on bool: both true and false must be covered");
+                }
+            }
+            Some(t) => panic!("Non-exhaustive match on {:?}: add a `_` arm", t),
+            None => {}
+        }
+    }
+
+    fn impl_block(&mut self) -> Node {
+        self.eat(TokenType::Impl);
+
+        let name = self.qualify(&self.current().get_value());
+        self.eat(TokenType::ID);
+        let self_type = if self.struct_types.contains_key(&name) {
+            Type::Struct(name.clone())
+        } else if self.enum_types.contains_key(&name) {
+            Type::Enum(name.clone())
+        } else {
+            panic!("impl for unknown type '{}'", name);
+        };
+        self.eat(TokenType::LBracket);
+
+        self.current_impl = Some(name);
+        self.variable_types.insert("self".to_string(), self_type);
+
+        let mut methods: Vec<functionNode> = Vec::new();
+        while self.current().get_type() != TokenType::RBracket {
+            match self.custom_func() {
+                Node::FunctionNode(func) => methods.push(func),
+                _ => unreachable!(),
+            }
+        }
+        self.eat(TokenType::RBracket);
+
+        self.current_impl = None;
+        Node::ImplNode(methods)
+    }
+
+    fn for_statement(&mut self) -> Node {
+        self.eat(TokenType::For);
+
+        let var_name = self.ident("a loop variable");
+        self.eat(TokenType::In);
+
+        let iterable = self.expr_as(None);
+        let var_type = match self.expr_type(&iterable) {
+            Some(Type::List(elem)) => *elem,
+            Some(Type::Map(key, _)) => *key,
+            Some(t) => panic!("Cannot iterate over {:?}", t),
+            None => panic!("Cannot infer type of loop variable '{}'", var_name),
+        };
+        let saved = self.shadow_scoped(&var_name, var_type);
+        let body = self.parse_scope();
+        self.restore(vec![saved]);
+        Node::For(forNode::new(var_name, iterable, body))
+    }
+
+    fn if_let_statement(&mut self) -> Node {
+        self.eat(TokenType::If);
+        self.eat(TokenType::Let);
+
+        let name = self.ident("a variable");
+        self.eat(TokenType::Assignment);
+
+        let value = self.expr_as(None);
+        let inner = match self.expr_type(&value) {
+            Some(Type::Optional(inner)) if *inner != Type::Void => *inner,
+            Some(Type::Optional(_)) => panic!("Cannot unwrap none into '{}'", name),
+            Some(t) => panic!("`if let` needs an optional value, got {:?}", t),
+            None => panic!("Cannot infer type of '{}' in `if let`", name),
+        };
+
+        //внутри блока имя связано с развёрнутым значением
+        let saved = self.shadow_scoped(&name, inner);
+        let body = self.parse_scope();
+        self.restore(vec![saved]);
+
+        let else_body =
+            if self.pos < self.tokens_clone.len() && self.current().get_type() == TokenType::Else {
+                self.eat(TokenType::Else);
+                Some(self.parse_scope())
+            } else {
+                None
+            };
+        Node::IfLet(ifLetNode::new(name, value, body, else_body))
+    }
+
+    fn try_statement(&mut self) -> Node {
+        self.eat(TokenType::Try);
+        let body = self.parse_scope();
+
+        self.eat(TokenType::Catch);
+        let error_name = self.ident("a variable");
+        let saved = self.shadow_scoped(&error_name, Type::Struct("Error".to_string()));
+        let handler = self.parse_scope();
+        self.restore(vec![saved]);
+        Node::Try(tryNode::new(body, error_name, handler))
+    }
+
+    fn parse_type(&mut self) -> Type {
+        let t = self.parse_base_type();
+
+        if self.pos < self.tokens_clone.len() && self.current().get_type() == TokenType::Question {
+            self.eat(TokenType::Question);
+            if matches!(t, Type::Void | Type::Optional(_)) {
+                panic!("Type {:?} cannot be optional", t);
+            }
+            return Type::Optional(Box::new(t));
+        }
+        t
+    }
+
+    fn parse_base_type(&mut self) -> Type {
+        let t = match self.current().get_type() {
+            TokenType::IntType => Type::Int,
+            TokenType::StringType => Type::String,
+            TokenType::BoolType => Type::Bool,
+            TokenType::CharType => Type::Char,
+            TokenType::LongType => Type::Long,
+            TokenType::ShortType => Type::Short,
+            TokenType::FloatType => Type::Float,
+            TokenType::VoidType => Type::Void,
+            TokenType::LSqBracket => {
+                self.eat(TokenType::LSqBracket);
+                let elem = self.parse_type();
+                self.eat(TokenType::RSqBracket);
+                return Type::List(Box::new(elem));
+            }
+            TokenType::LBracket => {
+                self.eat(TokenType::LBracket);
+                let key = self.parse_type();
+                if !Self::is_key_type(&key) {
+                    panic!("Type {:?} cannot be a map key", key);
+                }
+                self.eat(TokenType::Colon);
+                let value = self.parse_type();
+                self.eat(TokenType::RBracket);
+                return Type::Map(Box::new(key), Box::new(value));
+            }
+            TokenType::Fn => {
+                self.eat(TokenType::Fn);
+                self.eat(TokenType::LParen);
+                let mut args: Vec<Type> = Vec::new();
+                while self.current().get_type() != TokenType::RParen {
+                    args.push(self.parse_type());
+                    if self.current().get_type() == TokenType::Coma {
+                        self.eat(TokenType::Coma);
+                    } else {
+                        break;
+                    }
+                }
+                self.eat(TokenType::RParen);
+
+                let ret = if self.pos < self.tokens_clone.len()
+                    && self.current().get_type() == TokenType::RetOp
+                {
+                    self.eat(TokenType::RetOp);
+                    self.parse_type()
+                } else {
+                    Type::Void
+                };
+                return Type::Function(args, Box::new(ret));
+            }
+            TokenType::ID if self.type_ref().is_some() => {
+                let key = self.eat_type_ref();
+                return match self.struct_types.contains_key(&key) {
+                    true => Type::Struct(key),
+                    false => Type::Enum(key),
+                };
+            }
+            _ => panic!("Unexpected type: {}", self.current().get_value()),
+        };
+
+        self.eat(self.current().get_type());
+        t
+    }
+
+    fn declaration(&mut self) -> Node {
+        let name = self.current().get_value();
+        self.eat(TokenType::ID);
+        self.eat(TokenType::Colon);
+
+        let t = self.parse_type();
+        if t == Type::Void {
+            panic!("Cannot declare variable '{}' of type void", name);
+        }
+        self.declare(&name, t.clone());
+
+        if self.pos < self.tokens_clone.len() && self.current().get_type() == TokenType::Assignment
+        {
+            self.eat(TokenType::Assignment);
+            let expr = self.expr_as(Some(t.clone()));
+            self.check_unwrapped(&t, &expr, &format!("variable '{}'", name));
+
+            let extype = match &expr {
+                expressionNode::StringLiteral(_) => t == Type::String,
+                expressionNode::DefaultValue(_) => true,
+                expressionNode::Variable(var) => var.get_type() == t,
+                expressionNode::Number(_)
+                | expressionNode::LongExpression(_)
+                | expressionNode::ShortExpression(_)
+                | expressionNode::FloatExpression(_)
+                | expressionNode::ListLiteral(_)
+                | expressionNode::MapLiteral(_)
+                | expressionNode::StructLiteral(_)
+                | expressionNode::Field(_)
+                | expressionNode::MethodCall(_)
+                | expressionNode::EnumLiteral(_)
+                | expressionNode::Match(_)
+                | expressionNode::Lambda(_)
+                | expressionNode::FunctionRef(_)
+                | expressionNode::CallValue(_)
+                | expressionNode::Index(_)
+                | expressionNode::Slice(_)
+                | expressionNode::Cast(_) => {
+                    if let Some(actual) = self.expr_type(&expr)
+                        && !Self::type_fits(&t, &actual)
+                    {
+                        panic!(
+                            "Type mismatch for variable '{}': expected {:?}, got {:?}",
+                            name, t, actual
+                        );
+                    }
+                    true
+                }
+
+                expressionNode::BinOp(_) => {
+                    fn is_binop_type_correct(
+                        parser: &Parser,
+                        expr: &expressionNode,
+                        expected: &Type,
+                    ) -> bool {
+                        match expr {
+                            expressionNode::BinOp(op)
+                                if op.get_op() == "==" || op.get_op() == "!=" =>
+                            {
+                                expected == &Type::Bool
+                            }
+                            expressionNode::BinOp(op) if op.get_op() == "??" => parser
+                                .expr_type(expr)
+                                .is_none_or(|actual| Parser::type_fits(expected, &actual)),
+                            expressionNode::BinOp(op) => {
+                                is_binop_type_correct(parser, &op.get_left(), expected)
+                                    && is_binop_type_correct(parser, &op.get_right(), expected)
+                            }
+                            expressionNode::Number(_) => expected == &Type::Int,
+                            expressionNode::StringLiteral(_) => expected == &Type::String,
+                            expressionNode::Bool(_) => expected == &Type::Bool,
+                            expressionNode::CharLiteral(_) => expected == &Type::Char,
+                            expressionNode::FloatExpression(_) => expected == &Type::Float,
+                            expressionNode::LongExpression(_) => expected == &Type::Long,
+                            expressionNode::ShortExpression(_) => expected == &Type::Short,
+                            expressionNode::Variable(var) => &var.get_type() == expected,
+                            _ => parser
+                                .expr_type(expr)
+                                .is_none_or(|actual| Parser::type_fits(expected, &actual)),
+                        }
+                    }
+
+                    if !is_binop_type_correct(self, &expr, &t) {
+                        panic!("Type mismatch in binop for variable '{}'", name);
+                    }
+                    true
+                }
+
+                _ => false,
+            };
+
+            //if !extype {
+            //panic!("Type not need {:?}, got {:?}", t, expr);
+            //}
+
+            return Node::Assignment(assignmentNode::new(variableNode::new(name, t), expr));
+        }
+
+        if let Type::Enum(enum_name) = &t {
+            panic!(
+                "Variable '{}' of enum type '{}' must be initialized",
+                name, enum_name
+            );
+        }
+
+        Node::Assignment(assignmentNode::new(
+            variableNode::new(name, t.clone()),
+            expressionNode::DefaultValue(t),
+        ))
+    }
+
+    fn parse_scope(&mut self) -> scopeNode {
+        self.eat(TokenType::LBracket);
+        let mut nodes: Vec<Node> = Vec::new();
+        nodes = self.statement_list();
+        self.eat(TokenType::RBracket);
+        scopeNode::new(nodes)
+    }
+
+    fn custom_func(&mut self) -> Node {
+        self.eat(TokenType::Fn);
+
+        //обявления имя функции
+        let name = self.ident("a function");
+
+        //методы хранятся как "Struct.method"
+        let name = match &self.current_impl {
+            Some(struct_name) => format!("{}.{}", struct_name, name),
+            None => name,
+        };
+
+        self.eat(TokenType::LParen);
+
+        //парс аргументов
+        let mut args: Vec<Arg> = Vec::new();
+        while self.current().get_type() != TokenType::RParen {
+            let arg_name = self.ident("an argument");
+            self.eat(TokenType::Colon);
+
+            let arg_type = self.parse_type();
+
+            args.push(Arg {
+                name: arg_name,
+                arg_type,
+            });
+
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
+            }
+        }
+
+        self.eat(TokenType::RParen);
+        let mut saved = Vec::new();
+        for arg in &args {
+            //println!("arg : {}",arg.name);
+            saved.push(self.shadow_scoped(&arg.name, arg.arg_type.clone()));
+        }
+
+        self.eat(TokenType::RetOp);
+
+        //обработка возращаемого значения
+        let return_val = self.parse_type();
+        let arg_types = args.iter().map(|a| a.arg_type.clone()).collect();
+        self.function_types.insert(
+            name.clone(),
+            Type::Function(arg_types, Box::new(return_val.clone())),
+        );
+
+        self.eat(TokenType::LBracket);
+
+        let body_nodes = self.function_body(return_val.clone(), Self::statement_list);
+        self.restore(saved);
+
+        self.eat(TokenType::RBracket);
+
+        let scope = scopeNode::new(body_nodes);
+
+        let func = Node::FunctionNode(functionNode::new(name, args, scope, return_val));
+
+        func
+    }
+
+    fn expr(&mut self) -> expressionNode {
+        let left = self.equality();
+        self.coalesce_tail(left)
+    }
+
+    // целые литералы без суффикса получают тип из контекста: объявления, аргумента, операнда
+    fn with_literal_type(
+        &mut self,
+        t: Option<Type>,
+        parse: fn(&mut Self) -> expressionNode,
+    ) -> expressionNode {
+        let outer = std::mem::replace(&mut self.literal_type, t);
+        let expr = parse(self);
+        self.literal_type = outer;
+        expr
+    }
+
+    fn expr_as(&mut self, t: Option<Type>) -> expressionNode {
+        self.with_literal_type(t, Self::expr)
+    }
+
+    // правый операнд берёт числовой тип левого: big + 5000000000
+    fn operand_type(&self, left: &expressionNode) -> Option<Type> {
+        match self.expr_type(left) {
+            Some(t @ (Type::Int | Type::Long | Type::Short)) => Some(t),
+            _ => self.literal_type.clone(),
+        }
+    }
+
+    // a ?? b ?? c разбирается справа: a ?? (b ?? c)
+    fn coalesce_tail(&mut self, left: expressionNode) -> expressionNode {
+        if self.pos < self.tokens_clone.len()
+            && self.current().get_type() == TokenType::Operator
+            && self.current().get_value() == "??"
+        {
+            let tok = self.current().clone();
+            self.eat(TokenType::Operator);
+            let context = match self.expr_type(&left) {
+                Some(Type::Optional(inner)) => Some(*inner),
+                _ => self.literal_type.clone(),
+            };
+            let right = self.expr_as(context);
+            let binop = binOpNode::new(left, right, tok.get_value(), tok.get_position());
+            let expr = expressionNode::BinOp(Box::new(binop));
+            self.expr_type(&expr);
+            return expr;
+        }
+        left
+    }
+
+    // приоритеты как в Rust: ** > * / % > + - > << >> > & > ^ > | > == !=
+    fn equality(&mut self) -> expressionNode {
+        let left = self.bit_or();
+        self.binary_tail(left, &["==", "!="], Self::bit_or)
+    }
+
+    fn bit_or(&mut self) -> expressionNode {
+        let left = self.bit_xor();
+        self.binary_tail(left, &["|"], Self::bit_xor)
+    }
+
+    fn bit_xor(&mut self) -> expressionNode {
+        let left = self.bit_and();
+        self.binary_tail(left, &["^"], Self::bit_and)
+    }
+
+    fn bit_and(&mut self) -> expressionNode {
+        let left = self.shift();
+        self.binary_tail(left, &["&"], Self::shift)
+    }
+
+    fn shift(&mut self) -> expressionNode {
+        let left = self.sum();
+        self.binary_tail(left, &["<<", ">>"], Self::sum)
+    }
+
+    fn bin_op(&self, left: expressionNode, right: expressionNode, tok: &Token) -> expressionNode {
+        let op = tok.get_value();
+        if op != "==" && op != "!=" {
+            for side in [&left, &right] {
+                if let Some(t @ Type::Optional(_)) = self.expr_type(side) {
+                    panic!(
+                        "Optional {:?} must be unwrapped with `if let` or `??` before '{}'",
+                        t, op
+                    );
+                }
+            }
+        }
+        if matches!(op.as_str(), "&" | "|" | "^" | "<<" | ">>")
+            && let Some(t) = self.expr_type(&left)
+            && !Self::is_integer_type(&t)
+        {
+            panic!(
+                "Operator '{}' needs integer operands, got {:?} (at {})",
+                op,
+                t,
+                tok.get_position()
+            );
+        }
+        expressionNode::BinOp(Box::new(binOpNode::new(
+            left,
+            right,
+            op,
+            tok.get_position(),
+        )))
+    }
+
+    fn is_integer_type(t: &Type) -> bool {
+        matches!(t, Type::Int | Type::Long | Type::Short)
+    }
+
+    fn binary_tail(
+        &mut self,
+        mut left: expressionNode,
+        ops: &[&str],
+        operand: fn(&mut Self) -> expressionNode,
+    ) -> expressionNode {
+        while self.pos < self.tokens_clone.len() {
+            let tok = self.current().clone();
+            // `|` в начале выражения открывает лямбду, после операнда это битовое или
+            let is_op = match tok.get_type() {
+                TokenType::Operator | TokenType::Pipe => ops.contains(&tok.get_value().as_str()),
+                _ => false,
+            };
+            if !is_op {
+                break;
+            }
+            self.eat(tok.get_type());
+            let right = self.with_literal_type(self.operand_type(&left), operand);
+            left = self.bin_op(left, right, &tok);
+        }
+        left
+    }
+
+    fn sum(&mut self) -> expressionNode {
+        let left = self.term();
+        self.binary_tail(left, &["+", "-"], Self::term)
+    }
+
+    fn term(&mut self) -> expressionNode {
+        let left = self.unary();
+        self.binary_tail(left, &["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> expressionNode {
+        let tok = self.current().clone();
+        if tok.get_type() != TokenType::Operator
+            || !matches!(tok.get_value().as_str(), "-" | "+" | "!" | "~")
+        {
+            return self.power();
+        }
+        self.eat(TokenType::Operator);
+
+        // -2147483648 это один литерал, а не минус от слишком большого int
+        if tok.get_value() == "-" && self.is_negatable_literal() {
+            self.negative_literal = true;
+            return self.power();
+        }
+
+        let value = self.unary();
+        if let Some(t) = self.expr_type(&value) {
+            let fits = match tok.get_value().as_str() {
+                "!" => t == Type::Bool,
+                "~" => Self::is_integer_type(&t),
+                _ => Self::is_integer_type(&t) || t == Type::Float,
+            };
+            if !fits {
+                panic!(
+                    "Operator '{}' cannot be applied to {:?} (at {})",
+                    tok.get_value(),
+                    t,
+                    tok.get_position()
+                );
+            }
+        }
+        expressionNode::Unary(Box::new(unaryNode::new(
+            tok.get_value(),
+            value,
+            tok.get_position(),
+        )))
+    }
+
+    fn is_negatable_literal(&self) -> bool {
+        let is_int = matches!(
+            self.current().get_type(),
+            TokenType::IntLiteral | TokenType::LongLiteral | TokenType::ShortLiteral
+        );
+        // -2 ** 2 это -(2 ** 2)
+        let before_power = self
+            .tokens_clone
+            .get(self.pos + 1)
+            .is_some_and(|next| next.get_type() == TokenType::Operator && next.get_value() == "**");
+        is_int && !before_power
+    }
+
+    // ** правоассоциативен: 2 ** 3 ** 2 == 2 ** 9
+    fn power(&mut self) -> expressionNode {
+        let base = self.factor();
+        if self.pos < self.tokens_clone.len()
+            && self.current().get_type() == TokenType::Operator
+            && self.current().get_value() == "**"
+        {
+            let tok = self.current().clone();
+            self.eat(TokenType::Operator);
+            let exponent = self.with_literal_type(self.operand_type(&base), Self::unary);
+            return self.bin_op(base, exponent, &tok);
+        }
+        base
+    }
+
+    fn factor(&mut self) -> expressionNode {
+        let primary = self.primary();
+        let value = self.postfix_tail(primary);
+        self.cast_tail(value)
+    }
+
+    // `x as float`, сильнее `*` и слабее постфиксов
+    fn cast_tail(&mut self, mut left: expressionNode) -> expressionNode {
+        while self.pos < self.tokens_clone.len() && self.current().get_type() == TokenType::As {
+            let pos = self.current().get_position();
+            self.eat(TokenType::As);
+            let target = self.parse_type();
+            if let Some(from) = self.expr_type(&left)
+                && !Self::castable(&from, &target)
+            {
+                panic!("Cannot cast {:?} to {:?} (at {})", from, target, pos);
+            }
+            left = expressionNode::Cast(Box::new(castNode::new(left, target, pos)));
+        }
+        left
+    }
+
+    fn castable(from: &Type, to: &Type) -> bool {
+        if Self::type_fits(to, from) {
+            return true;
+        }
+        match (from, to) {
+            (Type::Optional(from), Type::Optional(to)) => Self::castable(from, to),
+            (_, Type::Optional(to)) => Self::castable(from, to),
+            (Type::Bool, Type::Int | Type::Long | Type::Short) => true,
+            (Type::Char, Type::Int | Type::Long | Type::Short) => true,
+            (Type::Int | Type::Long | Type::Short, Type::Char) => true,
+            (
+                Type::Int | Type::Long | Type::Short | Type::Float,
+                Type::Int | Type::Long | Type::Short | Type::Float,
+            ) => true,
+            _ => false,
+        }
+    }
+
+    fn postfix_tail(&mut self, mut left: expressionNode) -> expressionNode {
+        while self.pos < self.tokens_clone.len() {
+            let pos = self.current().get_position();
+            if matches!(
+                self.current().get_type(),
+                TokenType::Dot | TokenType::LSqBracket | TokenType::LParen
+            ) && let Some(t @ Type::Optional(_)) = self.expr_type(&left)
+            {
+                panic!(
+                    "Optional {:?} must be unwrapped with `if let` or `??` before use",
+                    t
+                );
+            }
+            if self.current().get_type() == TokenType::Dot {
+                self.eat(TokenType::Dot);
+                let name = self.current().get_value();
+                self.eat(TokenType::ID);
+
+                if self.pos < self.tokens_clone.len()
+                    && self.current().get_type() == TokenType::LParen
+                {
+                    let method = match self.expr_type(&left) {
+                        Some(Type::Struct(type_name) | Type::Enum(type_name)) => {
+                            Some(format!("{}.{}", type_name, name))
+                        }
+                        _ => None,
+                    };
+                    let callee = method.as_ref().and_then(|m| self.callee_type(m));
+                    let args = self.call_args(callee.clone());
+                    if let Some(method) = &method {
+                        self.check_call_args(method, callee, &args);
+                    }
+                    left = expressionNode::MethodCall(Box::new(methodCallNode::new(
+                        left, name, args, pos,
+                    )));
+                } else {
+                    left = expressionNode::Field(Box::new(fieldNode::new(left, name, pos)));
+                }
+                // проверка что поле или метод существует
+                self.expr_type(&left);
+                continue;
+            }
+
+            //вызов результата выражения, например make_adder(1)(2)
+            if self.current().get_type() == TokenType::LParen
+                && matches!(self.expr_type(&left), Some(Type::Function(_, _)))
+            {
+                let callee = self.expr_type(&left);
+                let args = self.call_args(callee.clone());
+                self.check_call_args("value", callee, &args);
+                left = expressionNode::CallValue(Box::new(callValueNode::new(left, args, pos)));
+                continue;
+            }
+
+            if self.current().get_type() != TokenType::LSqBracket {
+                break;
+            }
+            self.eat(TokenType::LSqBracket);
+
+            let start = if self.current().get_type() == TokenType::Colon {
+                None
+            } else {
+                let key = match self.expr_type(&left) {
+                    Some(Type::Map(key, _)) => Some(*key),
+                    _ => None,
+                };
+                Some(self.expr_as(key))
+            };
+
+            if self.current().get_type() == TokenType::Colon {
+                self.eat(TokenType::Colon);
+                let end = if self.current().get_type() == TokenType::RSqBracket {
+                    None
+                } else {
+                    Some(self.expr_as(None))
+                };
+                self.eat(TokenType::RSqBracket);
+                left = expressionNode::Slice(Box::new(sliceNode::new(left, start, end, pos)));
+            } else {
+                self.eat(TokenType::RSqBracket);
+                let index = start.expect("Expected index expression");
+                left = expressionNode::Index(Box::new(indexNode::new(left, index, pos)));
+            }
+        }
+        left
+    }
+
+    fn param_type(callee: &Option<Type>, i: usize) -> Option<Type> {
+        match callee {
+            Some(Type::Function(params, _)) => params.get(i).cloned(),
+            _ => None,
+        }
+    }
+
+    fn call_args(&mut self, callee: Option<Type>) -> Vec<expressionNode> {
+        self.eat(TokenType::LParen);
+
+        let mut args: Vec<expressionNode> = Vec::new();
+        while self.current().get_type() != TokenType::RParen {
+            args.push(self.expr_as(Self::param_type(&callee, args.len())));
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
+            }
+        }
+        self.eat(TokenType::RParen);
+        args
+    }
+
+    fn struct_literal(&mut self, name: String) -> expressionNode {
+        self.eat(TokenType::LBracket);
+
+        let mut values: Vec<(String, expressionNode)> = Vec::new();
+        while self.current().get_type() != TokenType::RBracket {
+            let field_name = self.current().get_value();
+            self.eat(TokenType::ID);
+            self.eat(TokenType::Colon);
+            let field_type = self.struct_types[&name]
+                .iter()
+                .find(|f| f.name == field_name)
+                .map(|f| f.field_type.clone());
+            let value = self.expr_as(field_type);
+            values.push((field_name, value));
+
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
+            }
+        }
+        self.eat(TokenType::RBracket);
+
+        let fields = self.struct_types[&name].clone();
+        for (field_name, value) in &values {
+            let Some(field) = fields.iter().find(|f| &f.name == field_name) else {
+                panic!("Struct '{}' has no field '{}'", name, field_name);
+            };
+            if values.iter().filter(|(n, _)| n == field_name).count() > 1 {
+                panic!("Field '{}' is set twice in '{}' literal", field_name, name);
+            }
+            if let Some(value_type) = self.expr_type(value)
+                && !Self::type_fits(&field.field_type, &value_type)
+            {
+                panic!(
+                    "Type mismatch for field '{}.{}': expected {:?}, got {:?}",
+                    name, field_name, field.field_type, value_type
+                );
+            }
+        }
+        for field in &fields {
+            if !values.iter().any(|(n, _)| n == &field.name) {
+                panic!("Missing field '{}' in '{}' literal", field.name, name);
+            }
+        }
+
+        expressionNode::StructLiteral(structLiteralNode::new(name, values))
+    }
+
+    fn field_type(&self, struct_name: &str, field: &str) -> Type {
+        self.struct_types
+            .get(struct_name)
+            .and_then(|fields| fields.iter().find(|f| f.name == field))
+            .map(|f| f.field_type.clone())
+            .unwrap_or_else(|| panic!("Struct '{}' has no field '{}'", struct_name, field))
+    }
+
+    fn lambda(&mut self) -> expressionNode {
+        self.eat(TokenType::Pipe);
+
+        self.captured
+            .push(self.variable_types.keys().cloned().collect());
+        let mut args: Vec<Arg> = Vec::new();
+        let mut saved = Vec::new();
+        while self.current().get_type() != TokenType::Pipe {
+            let arg_name = self.ident("an argument");
+            self.eat(TokenType::Colon);
+            let arg_type = self.parse_type();
+            saved.push(self.shadow_scoped(&arg_name, arg_type.clone()));
+            args.push(Arg {
+                name: arg_name,
+                arg_type,
+            });
+
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
+            }
+        }
+        self.eat(TokenType::Pipe);
+
+        let declared = if self.current().get_type() == TokenType::RetOp {
+            self.eat(TokenType::RetOp);
+            Some(self.parse_type())
+        } else {
+            None
+        };
+
+        //тело: блок или одно выражение, которое сразу возвращается
+        let (scope, return_val) = if self.current().get_type() == TokenType::LBracket {
+            let return_val = declared.unwrap_or(Type::Void);
+            let scope = self.function_body(return_val.clone(), Self::parse_scope);
+            (scope, return_val)
+        } else {
+            let body = self.expr_as(declared.clone());
+            let return_val = match (declared, self.expr_type(&body)) {
+                (Some(declared), Some(actual)) if !Self::type_fits(&declared, &actual) => {
+                    panic!("Lambda must return {:?}, got {:?}", declared, actual)
+                }
+                (Some(declared), _) => declared,
+                (None, Some(actual)) => actual,
+                (None, None) => panic!("Cannot infer lambda return type, add `-> type`"),
+            };
+            (
+                scopeNode::new(vec![Node::ReturnNode(returnNode::new(body))]),
+                return_val,
+            )
+        };
+        self.captured.pop();
+        self.restore(saved);
+
+        expressionNode::Lambda(functionNode::new(
+            "lambda".to_string(),
+            args,
+            scope,
+            return_val,
+        ))
+    }
+
+    // "a {x} b" приходит как InterpStart("a "), токены x, InterpEnd(" b")
+    fn interpolation(&mut self) -> expressionNode {
+        let mut parts: Vec<expressionNode> = Vec::new();
+        let start = self.current().get_value();
+        self.eat(TokenType::InterpStart);
+        if !start.is_empty() {
+            parts.push(expressionNode::StringLiteral(start));
+        }
+
+        loop {
+            let expr = self.expr_as(None);
+            match self.expr_type(&expr) {
+                Some(Type::Void) => panic!("Cannot interpolate a void value"),
+                Some(t @ Type::Function(_, _)) => panic!("Cannot interpolate a {:?} value", t),
+                _ => {}
+            }
+            parts.push(expr);
+
+            let tok = self.current().clone();
+            match tok.get_type() {
+                TokenType::InterpMid | TokenType::InterpEnd => {
+                    self.eat(tok.get_type());
+                    if !tok.get_value().is_empty() {
+                        parts.push(expressionNode::StringLiteral(tok.get_value()));
+                    }
+                    if tok.get_type() == TokenType::InterpEnd {
+                        break;
+                    }
+                }
+                _ => panic!(
+                    "Expected '}}' to close interpolation at {}, got {:?}",
+                    tok.get_position(),
+                    tok.get_value()
+                ),
+            }
+        }
+        expressionNode::Interpolation(parts)
+    }
+
+    fn int_literal(tok: &Token, target: Type, negative: bool) -> expressionNode {
+        let value = match negative {
+            true => format!("-{}", tok.get_value()),
+            false => tok.get_value(),
+        };
+        let overflow = || -> ! {
+            panic!(
+                "Integer literal {} does not fit in {:?} (at {})",
+                value,
+                target,
+                tok.get_position()
+            )
+        };
+        match target {
+            Type::Long => {
+                expressionNode::LongExpression(value.parse::<i128>().unwrap_or_else(|_| overflow()))
+            }
+            Type::Short => {
+                expressionNode::ShortExpression(value.parse::<i8>().unwrap_or_else(|_| overflow()))
+            }
+            _ => expressionNode::Number(numberNode::new(
+                value.parse::<i32>().unwrap_or_else(|_| overflow()),
+            )),
+        }
+    }
+
+    fn list_literal(&mut self) -> expressionNode {
+        self.eat(TokenType::LSqBracket);
+
+        let elem = match &self.literal_type {
+            Some(Type::List(elem)) => Some(*elem.clone()),
+            _ => None,
+        };
+        let mut items: Vec<expressionNode> = Vec::new();
+        while self.current().get_type() != TokenType::RSqBracket {
+            items.push(self.expr_as(elem.clone()));
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
+            }
+        }
+        self.eat(TokenType::RSqBracket);
+
+        //все элементы должны быть одного типа
+        let literal = expressionNode::ListLiteral(items);
+        self.expr_type(&literal);
+        literal
+    }
+
+    fn builtin_shadowed(&self, name: &str) -> bool {
+        self.function_types.contains_key(name)
+            || matches!(self.variable_types.get(name), Some(Type::Function(_, _)))
+    }
+
+    fn called_type(&self, name: &str) -> Option<Type> {
+        match self
+            .variable_types
+            .get(name)
+            .or_else(|| self.function_types.get(name))
+        {
+            Some(Type::Function(_, ret)) => Some(*ret.clone()),
+            _ => None,
+        }
+    }
+
+    //тип выражения без выполнения, None - неизвестно (вызовы, пустые списки)
+    pub fn expr_type(&self, expr: &expressionNode) -> Option<Type> {
+        match expr {
+            expressionNode::Number(_) => Some(Type::Int),
+            expressionNode::LongExpression(_) => Some(Type::Long),
+            expressionNode::ShortExpression(_) => Some(Type::Short),
+            expressionNode::FloatExpression(_) => Some(Type::Float),
+            expressionNode::StringLiteral(_) => Some(Type::String),
+            expressionNode::Interpolation(_) => Some(Type::String),
+            expressionNode::CharLiteral(_) => Some(Type::Char),
+            expressionNode::Bool(_) => Some(Type::Bool),
+            expressionNode::None => Some(Type::Optional(Box::new(Type::Void))),
+            expressionNode::DefaultValue(t) => Some(t.clone()),
+            expressionNode::Variable(var) => Some(var.get_type()),
+            expressionNode::FunctionCall(call) => {
+                let arg_type = |i: usize| call.get_argument(i).and_then(|a| self.expr_type(&a));
+                match call.get_function_name().as_str() {
+                    name if matches!(
+                        name,
+                        "len" | "push" | "pop" | "has" | "remove" | "keys" | "values"
+                    ) && self.builtin_shadowed(name) =>
+                    {
+                        self.called_type(name)
+                    }
+                    "len" => Some(Type::Int),
+                    "has" => Some(Type::Bool),
+                    "args" => Some(Type::List(Box::new(Type::String))),
+                    "env" => Some(Type::Optional(Box::new(Type::String))),
+                    "pop" => match arg_type(0) {
+                        Some(Type::List(elem)) => Some(*elem),
+                        _ => None,
+                    },
+                    "keys" => match arg_type(0) {
+                        Some(Type::Map(key, _)) => Some(Type::List(key)),
+                        _ => None,
+                    },
+                    "values" => match arg_type(0) {
+                        Some(Type::Map(_, value)) => Some(Type::List(value)),
+                        _ => None,
+                    },
+                    "remove" => match arg_type(0) {
+                        Some(Type::Map(_, value)) => Some(*value),
+                        _ => None,
+                    },
+                    name if strings::is_string_function(name) && !self.builtin_shadowed(name) => {
+                        Some(Self::string_type(name))
+                    }
+                    name if math::is_math_function(name) && !self.builtin_shadowed(name) => {
+                        Self::math_type(name, arg_type(0))
+                    }
+                    name if systemFunctions::is_time_function(name)
+                        && !self.builtin_shadowed(name) =>
+                    {
+                        Some(match name {
+                            "now_ms" => Type::Long,
+                            "clock" => Type::Float,
+                            "format_time" => Type::String,
+                            _ => Type::Void,
+                        })
+                    }
+                    name if random::is_random_function(name) && !self.builtin_shadowed(name) => {
+                        match (name, arg_type(0)) {
+                            ("random_int", first) => first,
+                            ("random_float", _) => Some(Type::Float),
+                            ("choice", Some(Type::List(elem))) => Some(*elem),
+                            ("choice", _) => None,
+                            _ => Some(Type::Void),
+                        }
+                    }
+                    name if files::is_file_function(name) && !self.builtin_shadowed(name) => {
+                        Some(Self::file_type(name))
+                    }
+                    name => self.called_type(name),
+                }
+            }
+            expressionNode::BinOp(op) => {
+                if op.get_op() == "==" || op.get_op() == "!=" {
+                    Some(Type::Bool)
+                } else if op.get_op() == "??" {
+                    self.coalesce_type(&op.get_left(), &op.get_right())
+                } else {
+                    self.expr_type(&op.get_left())
+                }
+            }
+            expressionNode::ListLiteral(items) => {
+                let mut elem: Option<Type> = None;
+                for item in items {
+                    let Some(item_type) = self.expr_type(item) else {
+                        continue;
+                    };
+                    elem = match elem {
+                        Some(t) => Some(Self::unify(&t, &item_type).unwrap_or_else(|| {
+                            panic!(
+                                "List elements must have the same type: {:?} and {:?}",
+                                t, item_type
+                            )
+                        })),
+                        None => Some(item_type),
+                    }
+                }
+                elem.map(|t| Type::List(Box::new(t)))
+            }
+            expressionNode::MapLiteral(entries) => {
+                let mut entry_types: Option<(Type, Type)> = None;
+                for (key, value) in entries {
+                    let (Some(key_type), Some(value_type)) =
+                        (self.expr_type(key), self.expr_type(value))
+                    else {
+                        continue;
+                    };
+                    entry_types = match entry_types {
+                        Some((k, v)) => match Self::unify(&v, &value_type) {
+                            Some(v) if Self::type_fits(&k, &key_type) => Some((k, v)),
+                            _ => panic!(
+                                "Map entries must have the same types: {{{:?}: {:?}}} and {{{:?}: {:?}}}",
+                                k, v, key_type, value_type
+                            ),
+                        },
+                        None => Some((key_type, value_type)),
+                    }
+                }
+                entry_types.map(|(k, v)| Type::Map(Box::new(k), Box::new(v)))
+            }
+            expressionNode::StructLiteral(literal) => Some(Type::Struct(literal.get_name())),
+            expressionNode::EnumLiteral(literal) => Some(Type::Enum(literal.get_enum_name())),
+            expressionNode::Lambda(func) => Some(Type::Function(
+                func.get_args().into_iter().map(|a| a.arg_type).collect(),
+                Box::new(func.get_ret_val()),
+            )),
+            expressionNode::FunctionRef(name) => self.function_types.get(name).cloned(),
+            expressionNode::CallValue(call) => match self.expr_type(&call.get_callee()) {
+                Some(Type::Function(_, ret)) => Some(*ret),
+                Some(t) => panic!("Type {:?} cannot be called", t),
+                None => None,
+            },
+            expressionNode::Match(node) => {
+                let mut result: Option<Type> = None;
+                for arm in node.get_arms() {
+                    let armBody::Expression(body) = arm.get_body() else {
+                        continue;
+                    };
+                    let Some(arm_type) = self.expr_type(&body) else {
+                        continue;
+                    };
+                    result = match result {
+                        Some(t) => Some(Self::unify(&t, &arm_type).unwrap_or_else(|| {
+                            panic!(
+                                "Match arms have different types: {:?} and {:?}",
+                                t, arm_type
+                            )
+                        })),
+                        None => Some(arm_type),
+                    }
+                }
+                result
+            }
+            expressionNode::Field(field) => match self.expr_type(&field.get_target()) {
+                Some(Type::Struct(name)) => Some(self.field_type(&name, &field.get_field())),
+                Some(t) => panic!("Type {:?} has no field '{}'", t, field.get_field()),
+                None => None,
+            },
+            expressionNode::MethodCall(call) => match self.expr_type(&call.get_target()) {
+                Some(Type::Struct(name)) | Some(Type::Enum(name)) => {
+                    let method = format!("{}.{}", name, call.get_method());
+                    match self.function_types.get(&method) {
+                        Some(Type::Function(_, ret)) => Some(*ret.clone()),
+                        Some(t) => Some(t.clone()),
+                        None => panic!("Type '{}' has no method '{}'", name, call.get_method()),
+                    }
+                }
+                Some(t) => panic!("Type {:?} has no method '{}'", t, call.get_method()),
+                None => None,
+            },
+            expressionNode::Index(index) => match self.expr_type(&index.get_target()) {
+                Some(Type::List(elem)) => Some(*elem),
+                Some(Type::Map(key, value)) => {
+                    if let Some(index_type) = self.expr_type(&index.get_index())
+                        && !Self::type_fits(&key, &index_type)
+                    {
+                        panic!("Map key must be {:?}, got {:?}", key, index_type);
+                    }
+                    Some(*value)
+                }
+                Some(t) => panic!("Type {:?} cannot be indexed", t),
+                None => None,
+            },
+            expressionNode::Slice(slice) => match self.expr_type(&slice.get_target()) {
+                Some(Type::List(elem)) => Some(Type::List(elem)),
+                Some(t) => panic!("Type {:?} cannot be sliced", t),
+                None => None,
+            },
+            expressionNode::Cast(cast) => Some(cast.get_target()),
+            expressionNode::Unary(unary) => self.expr_type(&unary.get_value()),
+        }
+    }
+
+    // abs, min, max, clamp, gcd, pow и округления сохраняют тип аргумента, остальное float
+    fn math_type(name: &str, first: Option<Type>) -> Option<Type> {
+        match name {
+            "abs" | "sign" | "min" | "max" | "clamp" | "gcd" | "pow" | "floor" | "ceil"
+            | "round" => first,
+            _ => Some(Type::Float),
+        }
+    }
+
+    fn file_type(name: &str) -> Type {
+        match name {
+            "read_file" => Type::String,
+            "read_lines" | "list_dir" => Type::List(Box::new(Type::String)),
+            "file_exists" => Type::Bool,
+            _ => Type::Void,
+        }
+    }
+
+    fn string_type(name: &str) -> Type {
+        match name {
+            "index_of" => Type::Optional(Box::new(Type::Int)),
+            "contains" | "starts_with" | "ends_with" => Type::Bool,
+            "split" => Type::List(Box::new(Type::String)),
+            "chars" => Type::List(Box::new(Type::Char)),
+            "char_at" => Type::Char,
+            "parse_int" => Type::Int,
+            "parse_float" => Type::Float,
+            _ => Type::String,
+        }
+    }
+
+    pub fn type_fits(expected: &Type, actual: &Type) -> bool {
+        match (expected, actual) {
+            // none подходит к любому опциональному типу
+            (Type::Optional(_), Type::Optional(inner)) if **inner == Type::Void => true,
+            (Type::Optional(e), Type::Optional(a)) => Self::type_fits(e, a),
+            (Type::Optional(e), a) => Self::type_fits(e, a),
+            _ => expected == actual,
+        }
+    }
+
+    // общий тип двух значений, например int и none дают int?
+    fn unify(a: &Type, b: &Type) -> Option<Type> {
+        let none = Type::Optional(Box::new(Type::Void));
+        if Self::type_fits(a, b) {
+            Some(a.clone())
+        } else if Self::type_fits(b, a) {
+            Some(b.clone())
+        } else if *a == none && *b != Type::Void {
+            Some(Type::Optional(Box::new(b.clone())))
+        } else if *b == none && *a != Type::Void {
+            Some(Type::Optional(Box::new(a.clone())))
+        } else {
+            None
+        }
+    }
+
+    fn coalesce_type(&self, left: &expressionNode, right: &expressionNode) -> Option<Type> {
+        let right_type = self.expr_type(right);
+        match self.expr_type(left) {
+            Some(Type::Optional(inner)) if *inner == Type::Void => right_type,
+            Some(Type::Optional(inner)) => match right_type {
+                Some(r) if !Self::type_fits(&Type::Optional(inner.clone()), &r) => {
+                    panic!("Default of `??` must be {:?}, got {:?}", inner, r)
+                }
+                Some(Type::Optional(_)) => Some(Type::Optional(inner)),
+                _ => Some(*inner),
+            },
+            Some(t) => panic!("Left side of `??` must be optional, got {:?}", t),
+            None => right_type,
+        }
+    }
+
+    // опциональное значение нельзя использовать как обычное без if let или ??
+    fn check_unwrapped(&self, expected: &Type, expr: &expressionNode, place: &str) {
+        if matches!(expected, Type::Optional(_)) {
+            return;
+        }
+        if let Some(actual @ Type::Optional(_)) = self.expr_type(expr) {
+            if actual == Type::Optional(Box::new(Type::Void)) {
+                panic!("none cannot be used as {:?} in {}", expected, place);
+            }
+            panic!(
+                "Optional {:?} must be unwrapped with `if let` or `??` before use as {:?} in {}",
+                actual, expected, place
+            );
+        }
+    }
+
+    fn callee_type(&self, name: &str) -> Option<Type> {
+        self.variable_types
+            .get(name)
+            .or_else(|| self.function_types.get(name))
+            .cloned()
+    }
+
+    fn check_call_args(&self, name: &str, callee: Option<Type>, args: &[expressionNode]) {
+        let Some(Type::Function(params, _)) = callee else {
+            return;
+        };
+        for (param, arg) in params.iter().zip(args) {
+            self.check_unwrapped(param, arg, &format!("call to '{}'", name));
+        }
+    }
+
+    fn is_key_type(t: &Type) -> bool {
+        matches!(
+            t,
+            Type::Int | Type::Long | Type::Short | Type::Char | Type::String | Type::Bool
+        )
+    }
+
+    fn map_literal(&mut self) -> expressionNode {
+        self.eat(TokenType::LBracket);
+
+        let (key_type, value_type) = match &self.literal_type {
+            Some(Type::Map(key, value)) => (Some(*key.clone()), Some(*value.clone())),
+            _ => (None, None),
+        };
+        let mut entries: Vec<(expressionNode, expressionNode)> = Vec::new();
+        while self.current().get_type() != TokenType::RBracket {
+            let key = self.expr_as(key_type.clone());
+            self.eat(TokenType::Colon);
+            let value = self.expr_as(value_type.clone());
+            entries.push((key, value));
+            if self.current().get_type() == TokenType::Coma {
+                self.eat(TokenType::Coma);
+            } else {
+                break;
+            }
+        }
+        self.eat(TokenType::RBracket);
+
+        //ключи и значения должны быть одного типа
+        let literal = expressionNode::MapLiteral(entries);
+        if let Some(Type::Map(key, _)) = self.expr_type(&literal)
+            && !Self::is_key_type(&key)
+        {
+            panic!("Type {:?} cannot be a map key", key);
+        }
+        literal
+    }
+
+    fn primary(&mut self) -> expressionNode {
+        let tok = self.current().clone();
+        match tok.get_type() {
+            TokenType::IntLiteral | TokenType::LongLiteral | TokenType::ShortLiteral => {
+                self.eat(tok.get_type());
+
+                //без суффикса тип берём из контекста, по умолчанию int
+                let target = match tok.get_type() {
+                    TokenType::LongLiteral => Type::Long,
+                    TokenType::ShortLiteral => Type::Short,
+                    _ => match &self.literal_type {
+                        Some(Type::Long) => Type::Long,
+                        Some(Type::Short) => Type::Short,
+                        Some(Type::Optional(inner)) if **inner == Type::Long => Type::Long,
+                        Some(Type::Optional(inner)) if **inner == Type::Short => Type::Short,
+                        _ => Type::Int,
+                    },
+                };
+                let negative = std::mem::take(&mut self.negative_literal);
+                Self::int_literal(&tok, target, negative)
+            }
+            TokenType::FloatLiteral => {
+                self.eat(TokenType::FloatLiteral);
+                let value = tok.get_value();
+                expressionNode::FloatExpression(
+                    value
+                        .parse::<f32>()
+                        .unwrap_or_else(|_| panic!("Invalid float literal '{}'", value)),
+                )
+            }
+            TokenType::StringLiteral => {
+                self.eat(TokenType::StringLiteral);
+                expressionNode::StringLiteral(tok.get_value())
+            }
+            TokenType::InterpStart => self.interpolation(),
             TokenType::CharLiteral => {
                 self.eat(TokenType::CharLiteral);
                 let val = tok.get_value();
                 let ch = val.chars().next().expect("Empty char literal");
                 expressionNode::CharLiteral(ch)
             }
+            TokenType::None => {
+                self.eat(TokenType::None);
+                expressionNode::None
+            }
             TokenType::True => {
                 self.eat(TokenType::True);
                 expressionNode::Bool((true))
@@ -416,6 +2566,7 @@ impl Parser {
             }
             TokenType::ID => {
                 let name = self.current().get_value();
+                let pos = self.current().get_position();
 
                 if self.tokens_clone.get(self.pos + 1).map(|t| t.get_type())
                     == Some(TokenType::LParen)
@@ -423,11 +2574,12 @@ impl Parser {
                     self.eat(TokenType::ID);
                     self.eat(TokenType::LParen);
 
+                    let callee = self.callee_type(&name);
                     let mut args: Vec<expressionNode> = Vec::new();
 
                     if self.current().get_type() != TokenType::RParen {
                         loop {
-                            args.push(self.expr());
+                            args.push(self.expr_as(Self::param_type(&callee, args.len())));
                             if self.current().get_type() == TokenType::Coma {
                                 self.eat(TokenType::Coma);
                             } else {
@@ -437,8 +2589,38 @@ impl Parser {
                     }
 
                     self.eat(TokenType::RParen);
+                    self.check_call_args(&name, callee, &args);
 
-                    expressionNode::FunctionCall(callNode::new(name, args))
+                    expressionNode::FunctionCall(callNode::new(name, args, pos))
+                } else if let Some(module) = self.modules.get(&name).cloned()
+                    && !self.variable_types.contains_key(&name)
+                    && self.tokens_clone.get(self.pos + 1).map(|t| t.get_type())
+                        == Some(TokenType::Dot)
+                {
+                    self.module_member(name, module)
+                } else if let Some((key, _)) = self.type_ref()
+                    && self.struct_types.contains_key(&key)
+                    && self.tokens_clone.get(self.pos + 1).map(|t| t.get_type())
+                        == Some(TokenType::LBracket)
+                {
+                    self.eat(TokenType::ID);
+                    self.struct_literal(key)
+                } else if let Some((key, _)) = self.type_ref()
+                    && self.enum_types.contains_key(&key)
+                    && self.tokens_clone.get(self.pos + 1).map(|t| t.get_type())
+                        == Some(TokenType::DoubleColon)
+                {
+                    self.eat(TokenType::ID);
+                    self.enum_literal(key)
+                } else if !self.variable_types.contains_key(&name)
+                    && self.function_types.contains_key(&name)
+                {
+                    self.eat(TokenType::ID);
+                    expressionNode::FunctionRef(name)
+                } else if let Some((literal, _)) = self.constants.get(&name) {
+                    let literal = literal.clone();
+                    self.eat(TokenType::ID);
+                    literal
                 } else {
                     self.eat(TokenType::ID);
 
@@ -457,10 +2639,59 @@ impl Parser {
                 self.eat(TokenType::RParen);
                 expr
             }
+            TokenType::LSqBracket => self.list_literal(),
+            TokenType::LBracket => self.map_literal(),
+            TokenType::Pipe => self.lambda(),
+            TokenType::Match => expressionNode::Match(Box::new(self.match_node(false))),
             _ => panic!("Unexpected token in factor(xyeta): {:?}", tok),
         }
     }
 
+    // импорты ищутся относительно этого файла
+    pub fn set_file(&mut self, path: &Path) {
+        self.file = path.to_path_buf();
+        if let Ok(path) = std::fs::canonicalize(path) {
+            let mut loader = self.loader.borrow_mut();
+            if let Some(root) = path.parent() {
+                loader.set_root(root.to_path_buf());
+            }
+            let _ = loader.begin(path);
+        }
+    }
+
+    // свои типы главного файла называются как есть, типы модуля - через его префикс
+    fn qualify(&self, name: &str) -> String {
+        match self.prefix.is_empty() {
+            true => name.to_string(),
+            false => format!("{}.{}", self.prefix, name),
+        }
+    }
+
+    // Type или alias.Type с текущего токена: ключ типа и сколько токенов он занимает
+    fn type_ref(&self) -> Option<(String, usize)> {
+        let token = |i: usize| self.tokens_clone.get(self.pos + i);
+        let name = token(0)?.get_value();
+        if let Some(module) = self.modules.get(&name)
+            && !self.variable_types.contains_key(&name)
+            && token(1).map(|t| t.get_type()) == Some(TokenType::Dot)
+        {
+            return module.type_key(&token(2)?.get_value()).map(|key| (key, 3));
+        }
+        //встроенный Error не принадлежит ни одному файлу
+        [self.qualify(&name), name]
+            .into_iter()
+            .find(|key| self.struct_types.contains_key(key) || self.enum_types.contains_key(key))
+            .map(|key| (key, 1))
+    }
+
+    fn eat_type_ref(&mut self) -> String {
+        let (key, len) = self
+            .type_ref()
+            .unwrap_or_else(|| panic!("Unknown type '{}'", self.current().get_value()));
+        self.pos += len;
+        key
+    }
+
     pub fn parse(&mut self, tokens: Vec<Token>) -> Vec<Node> {
         self.tokens_clone = tokens;
         self.pos = 0;
@@ -0,0 +1,58 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::language;
+
+// флаги, аргументы и ввод для примеров, которым нужно что-то кроме запуска
+fn setup(name: &str) -> (Vec<&'static str>, Vec<&'static str>, &'static str) {
+    match name {
+        "entry" => (vec!["--allow-env"], vec!["one", "two"], ""),
+        "files" => (vec!["--allow-fs"], vec![], ""),
+        "time" => (vec!["--deterministic"], vec![], ""),
+        "test" => (vec![], vec![], "bob\n"),
+        _ => (vec![], vec![], ""),
+    }
+}
+
+// каждый examples/*.wn сверяется с examples/expected/<имя>.out
+#[test]
+fn examples_match_expected_output() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let mut names: Vec<String> = fs::read_dir(&root)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wn"))
+        .map(|path| path.file_stem().unwrap().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert!(!names.is_empty());
+
+    let mut failed = Vec::new();
+    for name in &names {
+        let expected_path = root.join("expected").join(format!("{}.out", name));
+        let expected = match fs::read_to_string(expected_path) {
+            Ok(expected) => expected,
+            Err(_) => {
+                failed.push(format!("{}: no expected output", name));
+                continue;
+            }
+        };
+        let (flags, script_args, stdin) = setup(name);
+        let path = format!("examples/{}.wn", name);
+        let mut args = flags;
+        args.push(&path);
+        args.extend(script_args);
+        let run = language(&args, stdin, &[("USER", "winter")]);
+        if run.code != Some(0) {
+            failed.push(format!("{}: exit {:?}\n{}", name, run.code, run.stderr));
+        } else if run.stdout != expected {
+            failed.push(format!(
+                "{}: output differs\n--- expected\n{}--- got\n{}",
+                name, expected, run.stdout
+            ));
+        }
+    }
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{language, scratch, script};
+
+// 10^9 вызовов при глубине 10: до конца не дойдёт ни один лимит
+const BURN: &str = "fn burn(n: int) -> int {
+    total : int = 1
+    match n == 0 {
+        false => {
+            for x in [1, 2, 3, 4, 5, 6, 7, 8, 9, 10] {
+                total = total + burn(n - 1)
+            }
+        },
+        true => {}
+    }
+    return total
+}
+
+try {
+    println(burn(9))
+} catch e {
+    println(\"caught\", e.kind)
+}
+println(\"after\")
+";
+
+// try/catch не перехватывает превышение, программа завершается с кодом 1
+fn exceeded(run: &common::Run, message: &str) {
+    assert_eq!(run.code, Some(1), "stderr: {}", run.stderr);
+    assert!(
+        run.stderr.contains("error[LimitExceeded]"),
+        "stderr: {}",
+        run.stderr
+    );
+    assert!(run.stderr.contains(message), "stderr: {}", run.stderr);
+    assert!(!run.stderr.contains("panicked"), "stderr: {}", run.stderr);
+    assert!(!run.stdout.contains("caught"), "stdout: {}", run.stdout);
+    assert!(!run.stdout.contains("after"), "stdout: {}", run.stdout);
+}
+
+#[test]
+fn step_limit_stops_the_program() {
+    let dir = scratch("steps");
+    let path = script(&dir, BURN);
+    let run = language(&["run", "--max-steps=1000", &path], "", &[]);
+    exceeded(&run, "step limit of 1000 exceeded");
+}
+
+#[test]
+fn time_limit_stops_the_program() {
+    let dir = scratch("timeout");
+    let path = script(&dir, BURN);
+    let start = Instant::now();
+    let run = language(&["grade", "--max-steps=0", "--timeout=200", &path], "", &[]);
+    exceeded(&run, "time limit of 200 ms exceeded");
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn depth_limit_stops_the_program() {
+    let dir = scratch("depth");
+    let path = script(
+        &dir,
+        "fn down(n: int) -> int {
+    return down(n + 1)
+}
+
+try {
+    println(down(0))
+} catch e {
+    println(\"caught\", e.kind)
+}
+println(\"after\")
+",
+    );
+    let run = language(&["run", "--max-depth=50", &path], "", &[]);
+    exceeded(&run, "call depth limit of 50 exceeded");
+}
+
+#[test]
+fn memory_limit_stops_the_program() {
+    let dir = scratch("memory");
+    let path = script(
+        &dir,
+        "fn grow(s: string, n: int) -> int {
+    match n == 0 {
+        true => return len(s),
+        false => return grow(s + s, n - 1)
+    }
+}
+
+try {
+    println(grow(\"x\", 40))
+} catch e {
+    println(\"caught\", e.kind)
+}
+println(\"after\")
+",
+    );
+    let run = language(&["grade", "--max-memory=100000", &path], "", &[]);
+    exceeded(&run, "memory limit of 100000 bytes exceeded");
+}
+
+#[test]
+fn zero_removes_a_limit() {
+    let dir = scratch("unlimited");
+    let path = script(
+        &dir,
+        "fn down(n: int) -> int {
+    match n == 0 {
+        true => return 0,
+        false => return down(n - 1)
+    }
+}
+println(down(2000))
+",
+    );
+    let run = language(&["run", "--max-depth=0", &path], "", &[]);
+    assert_eq!(run.code, Some(0), "stderr: {}", run.stderr);
+    assert_eq!(run.stdout.trim(), "0");
+}
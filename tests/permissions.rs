@@ -0,0 +1,125 @@
+mod common;
+
+use std::fs;
+
+use common::{language, scratch, script};
+
+fn denied(run: &common::Run, capability: &str) {
+    assert_eq!(run.code, Some(1), "stderr: {}", run.stderr);
+    assert!(
+        run.stderr.contains("error[PermissionDenied]"),
+        "stderr: {}",
+        run.stderr
+    );
+    assert!(
+        run.stderr
+            .contains(&format!("permission denied: {}", capability)),
+        "stderr: {}",
+        run.stderr
+    );
+    assert!(!run.stderr.contains("panicked"), "stderr: {}", run.stderr);
+}
+
+#[test]
+fn grade_denies_file_reads() {
+    let dir = scratch("grade_read");
+    let data = dir.join("data.txt");
+    fs::write(&data, "secret").unwrap();
+    let path = script(
+        &dir,
+        &format!("println(read_file(\"{}\"))\n", data.display()),
+    );
+    let run = language(&["grade", &path], "", &[]);
+    denied(&run, "fs.read");
+    assert!(!run.stdout.contains("secret"));
+}
+
+#[test]
+fn grade_denies_file_writes() {
+    let dir = scratch("grade_write");
+    let target = dir.join("out.txt");
+    let path = script(
+        &dir,
+        &format!("write_file(\"{}\", \"x\")\n", target.display()),
+    );
+    denied(&language(&["grade", &path], "", &[]), "fs.write");
+    assert!(!target.exists());
+}
+
+#[test]
+fn grade_denies_env() {
+    let dir = scratch("grade_env");
+    let path = script(&dir, "println(env(\"USER\") ?? \"none\")\n");
+    let run = language(&["grade", &path], "", &[("USER", "winter")]);
+    denied(&run, "env");
+    assert!(!run.stdout.contains("winter"));
+}
+
+#[test]
+fn grade_denies_quit() {
+    let dir = scratch("grade_quit");
+    let path = script(&dir, "println(\"before\")\nquit(3)\nprintln(\"after\")\n");
+    let run = language(&["grade", &path], "", &[]);
+    denied(&run, "quit");
+    assert_eq!(run.stdout.trim(), "before");
+}
+
+#[test]
+fn denial_can_be_caught() {
+    let dir = scratch("grade_catch");
+    let path = script(
+        &dir,
+        "try {\n    env(\"USER\")\n} catch e {\n    println(e.kind)\n}\n",
+    );
+    let run = language(&["grade", &path], "", &[]);
+    assert_eq!(run.code, Some(0), "stderr: {}", run.stderr);
+    assert_eq!(run.stdout.trim(), "PermissionDenied");
+}
+
+#[test]
+fn flags_grant_capabilities_in_grade() {
+    let dir = scratch("grade_allow");
+    let path = script(&dir, "println(env(\"USER\") ?? \"none\")\nquit(3)\n");
+    let run = language(
+        &["grade", "--allow-env", "--allow-quit", &path],
+        "",
+        &[("USER", "winter")],
+    );
+    assert_eq!(run.code, Some(3), "stderr: {}", run.stderr);
+    assert_eq!(run.stdout.trim(), "winter");
+}
+
+// .. не выводит за пределы разрешённой папки
+#[test]
+fn allowed_root_rejects_traversal() {
+    let dir = scratch("traversal");
+    let root = dir.join("data");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("in.txt"), "inside").unwrap();
+    fs::write(dir.join("secret.txt"), "outside").unwrap();
+    let path = script(
+        &dir,
+        &format!(
+            "println(read_file(\"{0}/in.txt\"))\nprintln(read_file(\"{0}/../secret.txt\"))\n",
+            root.display()
+        ),
+    );
+    let flag = format!("--allow-fs={}", root.display());
+    let run = language(&["run", &flag, &path], "", &[]);
+    denied(&run, "fs.read");
+    assert!(run.stderr.contains("outside the allowed directories"));
+    assert!(run.stdout.contains("inside"));
+    assert!(!run.stdout.contains("outside"));
+}
+
+#[test]
+fn read_root_does_not_allow_writes() {
+    let dir = scratch("read_only");
+    let path = script(
+        &dir,
+        &format!("write_file(\"{}/out.txt\", \"x\")\n", dir.display()),
+    );
+    let flag = format!("--allow-fs-read={}", dir.display());
+    denied(&language(&["grade", &flag, &path], "", &[]), "fs.write");
+    assert!(!dir.join("out.txt").exists());
+}